@@ -0,0 +1,66 @@
+//! Transaction fee handling for `pallet-transaction-payment`.
+//!
+//! `DealWithFees` is meant to be plugged into the runtime as
+//! `CurrencyAdapter<Balances, DealWithFees<Runtime>>`. Fees and tips are split by
+//! `FeeBurnPercentage`: the burned share is dropped from total issuance and recorded in
+//! `BurnedTokens`/`TokenInfo`, and the rest is credited to the treasury account.
+
+use super::*;
+use frame_support::traits::OnUnbalanced;
+use sp_std::marker::PhantomData;
+
+/// Negative imbalance of the pallet's currency
+pub type NegativeImbalanceOf<T, I> = <<T as Config<I>>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// Fee handler burning `FeeBurnPercentage` of transaction fees and tips
+pub struct DealWithFees<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> OnUnbalanced<NegativeImbalanceOf<T, I>> for DealWithFees<T, I> {
+    fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalanceOf<T, I>>) {
+        if let Some(mut fees) = fees_then_tips.next() {
+            if let Some(tips) = fees_then_tips.next() {
+                tips.merge_into(&mut fees);
+            }
+            Self::on_unbalanced(fees);
+        }
+    }
+
+    fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<T, I>) {
+        let (burned, _) = Pallet::<T, I>::split_fee(amount);
+        if !burned.is_zero() {
+            BlockFeeBurn::<T, I>::mutate(|total| *total = total.saturating_add(burned));
+        }
+    }
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+    /// Burn `FeeBurnPercentage` of `fee` and credit the rest to the treasury
+    ///
+    /// Returns `(burned, to_treasury)`. A treasury share too small to create the treasury
+    /// account would be lost, so it is burned instead.
+    pub(crate) fn split_fee(fee: NegativeImbalanceOf<T, I>) -> (u128, u128) {
+        let total: u128 = fee.peek().saturated_into();
+        let burn = T::FeeBurnPercentage::get().mul_floor(total);
+        let (mut to_burn, to_treasury) = fee.split(burn.saturated_into());
+
+        let treasury = Self::treasury_account();
+        if T::Currency::total_balance(&treasury).is_zero()
+            && to_treasury.peek() < T::Currency::minimum_balance()
+        {
+            to_burn.subsume(to_treasury);
+        } else {
+            T::Currency::resolve_creating(&treasury, to_treasury);
+        }
+
+        // Dropping the imbalance reduces total issuance
+        let burned: u128 = to_burn.peek().saturated_into();
+        drop(to_burn);
+
+        if !burned.is_zero() {
+            Self::note_burned(burned, BurnReason::Fee);
+        }
+        (burned, total.saturating_sub(burned))
+    }
+}
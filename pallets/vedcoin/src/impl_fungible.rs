@@ -0,0 +1,267 @@
+//! Implementation of the `fungible` traits for the VedCoin pallet.
+//!
+//! Calls are forwarded to `T::Fungible`. Minting goes through the max-supply cap, and every
+//! mint and burn keeps `TokenInfo` and `BurnedTokens` in sync, so other pallets can use VedCoin
+//! as their currency without bypassing the supply accounting. The raw `Unbalanced` and
+//! `UnbalancedHold` writes and `shelve`/`restore` are unsupported, and `set_balance` falls back
+//! to the default, which mints or burns through the capped and accounted paths. Transfers go
+//! through the transfer policy of the `transfer` call, and transfers of held funds through its
+//! pause and compliance checks.
+
+use super::*;
+use frame_support::traits::tokens::{
+    fungible, DepositConsequence, Fortitude, Precision, Preservation, Provenance, Restriction,
+    WithdrawConsequence,
+};
+use sp_runtime::TokenError;
+
+type BalanceOf<T, I> = <<T as Config<I>>::Fungible as fungible::Inspect<
+    <T as frame_system::Config>::AccountId,
+>>::Balance;
+
+impl<T: Config<I>, I: 'static> fungible::Inspect<T::AccountId> for Pallet<T, I> {
+    type Balance = BalanceOf<T, I>;
+
+    fn total_issuance() -> Self::Balance {
+        <T::Fungible as fungible::Inspect<_>>::total_issuance()
+    }
+
+    fn active_issuance() -> Self::Balance {
+        <T::Fungible as fungible::Inspect<_>>::active_issuance()
+    }
+
+    fn minimum_balance() -> Self::Balance {
+        <T::Fungible as fungible::Inspect<_>>::minimum_balance()
+    }
+
+    fn total_balance(who: &T::AccountId) -> Self::Balance {
+        <T::Fungible as fungible::Inspect<_>>::total_balance(who)
+    }
+
+    fn balance(who: &T::AccountId) -> Self::Balance {
+        <T::Fungible as fungible::Inspect<_>>::balance(who)
+    }
+
+    fn reducible_balance(
+        who: &T::AccountId,
+        preservation: Preservation,
+        force: Fortitude,
+    ) -> Self::Balance {
+        <T::Fungible as fungible::Inspect<_>>::reducible_balance(who, preservation, force)
+    }
+
+    fn can_deposit(
+        who: &T::AccountId,
+        amount: Self::Balance,
+        provenance: Provenance,
+    ) -> DepositConsequence {
+        <T::Fungible as fungible::Inspect<_>>::can_deposit(who, amount, provenance)
+    }
+
+    fn can_withdraw(
+        who: &T::AccountId,
+        amount: Self::Balance,
+    ) -> WithdrawConsequence<Self::Balance> {
+        <T::Fungible as fungible::Inspect<_>>::can_withdraw(who, amount)
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::Unbalanced<T::AccountId> for Pallet<T, I> {
+    /// Dust can only come from `write_balance`, which is unsupported
+    fn handle_dust(_dust: fungible::Dust<T::AccountId, Self>) {}
+
+    /// Writing balances directly would bypass the max-supply cap and the supply accounting,
+    /// so it is rejected; minting and burning go through `Mutate` instead
+    fn write_balance(
+        _who: &T::AccountId,
+        _amount: Self::Balance,
+    ) -> Result<Option<Self::Balance>, DispatchError> {
+        Err(TokenError::Unsupported.into())
+    }
+
+    /// Total issuance is derived from the underlying currency and is never set directly
+    fn set_total_issuance(_amount: Self::Balance) {}
+
+    fn deactivate(amount: Self::Balance) {
+        <T::Fungible as fungible::Unbalanced<_>>::deactivate(amount)
+    }
+
+    fn reactivate(amount: Self::Balance) {
+        <T::Fungible as fungible::Unbalanced<_>>::reactivate(amount)
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::Mutate<T::AccountId> for Pallet<T, I> {
+    fn mint_into(who: &T::AccountId, amount: Self::Balance) -> Result<Self::Balance, DispatchError> {
+        Self::ensure_can_mint(amount.saturated_into())?;
+        let minted = <T::Fungible as fungible::Mutate<_>>::mint_into(who, amount)?;
+        Self::note_minted(minted.saturated_into());
+        Self::deposit_event(Event::TokensMinted {
+            minter: None,
+            to: who.clone(),
+            amount: minted.saturated_into(),
+        });
+        Ok(minted)
+    }
+
+    fn burn_from(
+        who: &T::AccountId,
+        amount: Self::Balance,
+        precision: Precision,
+        force: Fortitude,
+    ) -> Result<Self::Balance, DispatchError> {
+        let burned = <T::Fungible as fungible::Mutate<_>>::burn_from(who, amount, precision, force)?;
+        Self::note_burned(burned.saturated_into(), BurnReason::Voluntary);
+        Ok(burned)
+    }
+
+    /// Shelved funds would leave total issuance without being recorded as burned
+    fn shelve(
+        _who: &T::AccountId,
+        _amount: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        Err(TokenError::Unsupported.into())
+    }
+
+    /// Restored funds would enter total issuance without passing the max-supply cap
+    fn restore(
+        _who: &T::AccountId,
+        _amount: Self::Balance,
+    ) -> Result<Self::Balance, DispatchError> {
+        Err(TokenError::Unsupported.into())
+    }
+
+    fn transfer(
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: Self::Balance,
+        preservation: Preservation,
+    ) -> Result<Self::Balance, DispatchError> {
+        Self::check_transfer(source, dest, amount.saturated_into())?;
+        let transferred =
+            <T::Fungible as fungible::Mutate<_>>::transfer(source, dest, amount, preservation)?;
+        Self::settle_transfer(source, dest, transferred.saturated_into())?;
+        Ok(transferred)
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::InspectHold<T::AccountId> for Pallet<T, I> {
+    type Reason = <T::Fungible as fungible::InspectHold<T::AccountId>>::Reason;
+
+    fn total_balance_on_hold(who: &T::AccountId) -> Self::Balance {
+        <T::Fungible as fungible::InspectHold<_>>::total_balance_on_hold(who)
+    }
+
+    fn reducible_total_balance_on_hold(who: &T::AccountId, force: Fortitude) -> Self::Balance {
+        <T::Fungible as fungible::InspectHold<_>>::reducible_total_balance_on_hold(who, force)
+    }
+
+    fn balance_on_hold(reason: &Self::Reason, who: &T::AccountId) -> Self::Balance {
+        <T::Fungible as fungible::InspectHold<_>>::balance_on_hold(reason, who)
+    }
+
+    fn hold_available(reason: &Self::Reason, who: &T::AccountId) -> bool {
+        <T::Fungible as fungible::InspectHold<_>>::hold_available(reason, who)
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::UnbalancedHold<T::AccountId> for Pallet<T, I> {
+    /// Writing held balances directly would bypass the supply accounting, so it is rejected;
+    /// `MutateHold` goes through the accounted paths instead
+    fn set_balance_on_hold(
+        _reason: &Self::Reason,
+        _who: &T::AccountId,
+        _amount: Self::Balance,
+    ) -> DispatchResult {
+        Err(TokenError::Unsupported.into())
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::MutateHold<T::AccountId> for Pallet<T, I> {
+    fn hold(reason: &Self::Reason, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+        <T::Fungible as fungible::MutateHold<_>>::hold(reason, who, amount)
+    }
+
+    fn release(
+        reason: &Self::Reason,
+        who: &T::AccountId,
+        amount: Self::Balance,
+        precision: Precision,
+    ) -> Result<Self::Balance, DispatchError> {
+        <T::Fungible as fungible::MutateHold<_>>::release(reason, who, amount, precision)
+    }
+
+    fn burn_held(
+        reason: &Self::Reason,
+        who: &T::AccountId,
+        amount: Self::Balance,
+        precision: Precision,
+        force: Fortitude,
+    ) -> Result<Self::Balance, DispatchError> {
+        let burned = <T::Fungible as fungible::MutateHold<_>>::burn_held(
+            reason, who, amount, precision, force,
+        )?;
+        Self::note_burned(burned.saturated_into(), BurnReason::Slash);
+        Ok(burned)
+    }
+
+    fn transfer_on_hold(
+        reason: &Self::Reason,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: Self::Balance,
+        precision: Precision,
+        mode: Restriction,
+        force: Fortitude,
+    ) -> Result<Self::Balance, DispatchError> {
+        Self::ensure_not_paused(PausableCall::Transfer)?;
+        Self::ensure_can_send(source)?;
+        Self::ensure_can_receive(dest)?;
+        <T::Fungible as fungible::MutateHold<_>>::transfer_on_hold(
+            reason, source, dest, amount, precision, mode, force,
+        )
+    }
+
+    fn transfer_and_hold(
+        reason: &Self::Reason,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: Self::Balance,
+        precision: Precision,
+        preservation: Preservation,
+        force: Fortitude,
+    ) -> Result<Self::Balance, DispatchError> {
+        Self::ensure_not_paused(PausableCall::Transfer)?;
+        Self::ensure_can_send(source)?;
+        Self::ensure_can_receive(dest)?;
+        <T::Fungible as fungible::MutateHold<_>>::transfer_and_hold(
+            reason, source, dest, amount, precision, preservation, force,
+        )
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::InspectFreeze<T::AccountId> for Pallet<T, I> {
+    type Id = <T::Fungible as fungible::InspectFreeze<T::AccountId>>::Id;
+
+    fn balance_frozen(id: &Self::Id, who: &T::AccountId) -> Self::Balance {
+        <T::Fungible as fungible::InspectFreeze<_>>::balance_frozen(id, who)
+    }
+
+    fn can_freeze(id: &Self::Id, who: &T::AccountId) -> bool {
+        <T::Fungible as fungible::InspectFreeze<_>>::can_freeze(id, who)
+    }
+}
+
+impl<T: Config<I>, I: 'static> fungible::MutateFreeze<T::AccountId> for Pallet<T, I> {
+    fn set_freeze(id: &Self::Id, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+        <T::Fungible as fungible::MutateFreeze<_>>::set_freeze(id, who, amount)
+    }
+
+    fn extend_freeze(id: &Self::Id, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
+        <T::Fungible as fungible::MutateFreeze<_>>::extend_freeze(id, who, amount)
+    }
+
+    fn thaw(id: &Self::Id, who: &T::AccountId) -> DispatchResult {
+        <T::Fungible as fungible::MutateFreeze<_>>::thaw(id, who)
+    }
+}
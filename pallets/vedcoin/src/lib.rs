@@ -1,153 +1,3 @@
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use frame_support::{assert_ok, assert_noop, parameter_types, traits::{OnFinalize, OnInitialize}};
-    use sp_core::H256;
-    use frame_system as system;
-    use sp_runtime::{testing::Header, traits::{BlakeTwo256, IdentityLookup}, Perbill};
-
-    type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
-    type Block = frame_system::mocking::MockBlock<Test>;
-
-    frame_support::construct_runtime!(
-        pub enum Test where
-            Block = Block,
-            NodeBlock = Block,
-            UncheckedExtrinsic = UncheckedExtrinsic,
-        {
-            System: frame_system,
-            VedCoin: crate,
-        }
-    );
-
-    parameter_types! {
-        pub const BlockHashCount: u64 = 250;
-        pub const MaxSymbolLength: u32 = 8;
-        pub const ExistentialDeposit: u128 = 1;
-        pub const PalletId: frame_support::PalletId = frame_support::PalletId(*b"ved/coin");
-        pub const FeeBurnPercentage: Perbill = Perbill::from_percent(10);
-    }
-
-    impl system::Config for Test {
-        type BaseCallFilter = frame_support::traits::Everything;
-        type BlockWeights = ();
-        type BlockLength = ();
-        type DbWeight = ();
-        type Origin = Origin;
-        type Call = Call;
-        type Index = u64;
-        type BlockNumber = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<u64>;
-        type Header = Header;
-        type Event = Event;
-        type BlockHashCount = BlockHashCount;
-        type Version = ();
-        type PalletInfo = PalletInfo;
-        type AccountData = pallet_balances::AccountData<u128>;
-        type OnNewAccount = ();
-        type OnKilledAccount = ();
-        type SystemWeightInfo = ();
-        type SS58Prefix = ();
-        type OnSetCode = ();
-        type MaxConsumers = frame_support::traits::ConstU32<16>;
-    }
-
-    impl Config for Test {
-        type RuntimeEvent = Event;
-        type Currency = pallet_balances::Pallet<Test>;
-        type MaxSymbolLength = MaxSymbolLength;
-        type PalletId = PalletId;
-        type FeeBurnPercentage = FeeBurnPercentage;
-    }
-
-    parameter_types! {
-        pub const ExistentialDeposit2: u128 = 1;
-    }
-    impl pallet_balances::Config for Test {
-        type Balance = u128;
-        type DustRemoval = ();
-        type RuntimeEvent = Event;
-        type ExistentialDeposit = ExistentialDeposit2;
-        type AccountStore = System;
-        type WeightInfo = ();
-        type MaxLocks = (); type MaxReserves = (); type ReserveIdentifier = [u8; 8];
-        type FreezeIdentifier = (); type MaxFreezes = (); type MaxHolds = (); type HoldIdentifier = (); 
-    }
-
-    pub fn new_test_ext() -> sp_io::TestExternalities {
-        let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
-        pallet_balances::GenesisConfig::<Test> {
-            balances: vec![(1, 1_000_000), (2, 1_000_000), (3, 1_000_000)],
-        }.assimilate_storage(&mut t).unwrap();
-        t.into()
-    }
-
-    #[test]
-    fn initialize_token_works() {
-        new_test_ext().execute_with(|| {
-            assert_ok!(VedCoin::initialize_token(
-                Origin::root(),
-                b"VED".to_vec(),
-                b"VedCoin".to_vec(),
-                18,
-                1_000_000_000_000_000_000u128
-            ));
-            let info = VedCoin::token_info().unwrap();
-            assert_eq!(info.symbol, b"VED".to_vec());
-            assert_eq!(info.name, b"VedCoin".to_vec());
-            assert_eq!(info.decimals, 18);
-            assert_eq!(info.total_supply, 1_000_000_000_000_000_000u128);
-        });
-    }
-
-    #[test]
-    fn transfer_works() {
-        new_test_ext().execute_with(|| {
-            assert_ok!(VedCoin::initialize_token(Origin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18, 1_000_000_000_000_000_000u128));
-            assert_ok!(VedCoin::transfer(Origin::signed(1), 2, 100_000));
-        });
-    }
-
-    #[test]
-    fn mint_tokens_works() {
-        new_test_ext().execute_with(|| {
-            assert_ok!(VedCoin::initialize_token(Origin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18, 1_000_000_000_000_000_000u128));
-            assert_ok!(VedCoin::mint_tokens(Origin::root(), 1, 500_000));
-        });
-    }
-
-    #[test]
-    fn burn_tokens_works() {
-        new_test_ext().execute_with(|| {
-            assert_ok!(VedCoin::initialize_token(Origin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18, 1_000_000_000_000_000_000u128));
-            assert_ok!(VedCoin::burn_tokens(Origin::signed(1), 100_000));
-        });
-    }
-
-    #[test]
-    fn register_and_stake_works() {
-        new_test_ext().execute_with(|| {
-            assert_ok!(VedCoin::initialize_token(Origin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18, 1_000_000_000_000_000_000u128));
-            assert_ok!(VedCoin::register_validator(Origin::signed(1), 10));
-            assert_ok!(VedCoin::stake(Origin::signed(1), 1, 100_000));
-            assert_ok!(VedCoin::stake(Origin::signed(2), 1, 50_000));
-        });
-    }
-
-    #[test]
-    fn unstake_works() {
-        new_test_ext().execute_with(|| {
-            assert_ok!(VedCoin::initialize_token(Origin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18, 1_000_000_000_000_000_000u128));
-            assert_ok!(VedCoin::register_validator(Origin::signed(1), 10));
-            assert_ok!(VedCoin::stake(Origin::signed(1), 1, 100_000));
-            assert_ok!(VedCoin::unstake(Origin::signed(1), 1, 50_000));
-        });
-    }
-
-}
 #![cfg_attr(not(feature = "std"), no_std)]
 
 //! # VedCoin Pallet
@@ -157,23 +7,62 @@ mod tests {
 //! - Staking and delegation
 //! - Fee payment and burning
 //! - Cross-chain bridge support
+//!
+//! `Pallet` implements the `fungible` traits (`Inspect`, `Mutate`, `MutateHold`,
+//! `MutateFreeze`), so other pallets can take VedCoin as their currency type.
+//!
+//! The pallet is instantiable, so a single runtime can host both VedCoin and VedGov with
+//! separate storage and their own origin configuration.
 
 use frame_support::{
     codec::{Decode, Encode},
     dispatch::{DispatchError, DispatchResult},
-    traits::{Currency, Get, ReservableCurrency},
+    traits::{
+        tokens::fungible, BalanceStatus, Currency, Get, Imbalance, LockIdentifier, LockableCurrency,
+        NamedReservableCurrency, ReservableCurrency, WithdrawReasons,
+    },
     PalletId, RuntimeDebug,
 };
 use frame_system::ensure_signed;
 use scale_info::TypeInfo;
+use sp_core::H256;
 use sp_runtime::{
-    traits::{AccountIdConversion, Saturating, Zero},
+    traits::{
+        AccountIdConversion, AtLeast32BitUnsigned, IdentifyAccount, One, SaturatedConversion,
+        Saturating, Verify, Zero,
+    },
     Perbill,
 };
 use sp_std::vec::Vec;
 
 pub use pallet::*;
 
+mod fees;
+mod impl_fungible;
+pub mod migrations;
+mod sponsorship;
+
+pub use fees::{DealWithFees, NegativeImbalanceOf};
+pub use sponsorship::ChargeSponsored;
+
+/// Lock identifier for vested tokens
+pub const VESTING_ID: LockIdentifier = *b"vedvest ";
+
+/// Named reserve identifier for staked tokens
+pub const STAKING_ID: [u8; 8] = *b"vedstake";
+
+/// Named reserve identifier for fee sponsorship deposits
+pub const SPONSORSHIP_ID: [u8; 8] = *b"vedspons";
+
+/// Named reserve identifier for funds held in escrow
+pub const ESCROW_ID: [u8; 8] = *b"vedescrw";
+
+/// Named reserve identifier for payment stream deposits
+pub const STREAM_ID: [u8; 8] = *b"vedstrem";
+
+/// Named reserve identifier for funds locked in hash time-locked contracts
+pub const HTLC_ID: [u8; 8] = *b"vedhtlc ";
+
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
@@ -181,20 +70,44 @@ pub mod pallet {
     use frame_system::pallet_prelude::*;
 
     /// The current storage version.
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 
     #[pallet::pallet]
-    #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::without_storage_info]
     #[pallet::storage_version(STORAGE_VERSION)]
-    pub struct Pallet<T>(_);
+    pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
 
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config<I: 'static = ()>: frame_system::Config {
         /// The overarching event type.
-        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+        type RuntimeEvent: From<Event<Self, I>>
+            + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// Origin allowed to move tokens, yielding the sending account.
+        ///
+        /// Use `EnsureSigned` for an open token, or restrict it (e.g. `EnsureSignedBy`) for
+        /// instances such as VedGov where only registered government accounts may transfer.
+        type TransferOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+        /// Origin allowed to burn through `burn_from` with `BurnReason::Bridge`, yielding the
+        /// spending account.
+        type BridgeOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
 
         /// The currency used for fee payment.
-        type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+        ///
+        /// Every instance needs its own currency: reserve and lock identifiers such as
+        /// `STAKING_ID` and `VESTING_ID` are shared by all instances, so two instances on one
+        /// currency would release each other's reserves and overwrite each other's locks. The
+        /// supply counters are also checked against this currency's total issuance.
+        type Currency: Currency<Self::AccountId>
+            + ReservableCurrency<Self::AccountId>
+            + NamedReservableCurrency<Self::AccountId, ReserveIdentifier = [u8; 8]>
+            + LockableCurrency<Self::AccountId, Moment = Self::BlockNumber>;
+
+        /// The same balances as `Currency`, seen through the `fungible` traits.
+        type Fungible: fungible::Mutate<Self::AccountId>
+            + fungible::MutateHold<Self::AccountId>
+            + fungible::MutateFreeze<Self::AccountId>;
 
         /// The maximum length of a token symbol.
         #[pallet::constant]
@@ -207,6 +120,111 @@ pub mod pallet {
         /// Percentage of fees that should be burned (vs sent to treasury).
         #[pallet::constant]
         type FeeBurnPercentage: Get<Perbill>;
+
+        /// Hard cap on the total number of tokens that can ever be created.
+        #[pallet::constant]
+        type MaxSupply: Get<u128>;
+
+        /// Number of blocks in an emission year.
+        #[pallet::constant]
+        type BlocksPerYear: Get<Self::BlockNumber>;
+
+        /// Tokens emitted during the first emission year.
+        #[pallet::constant]
+        type InitialEmission: Get<u128>;
+
+        /// Tokens emitted per year after the first year, before any halving.
+        #[pallet::constant]
+        type AnnualEmission: Get<u128>;
+
+        /// Number of years between halvings of the annual emission.
+        #[pallet::constant]
+        type HalvingInterval: Get<u32>;
+
+        /// Maximum number of recipients in a single batch transfer.
+        #[pallet::constant]
+        type MaxBatchSize: Get<u32>;
+
+        /// Maximum length of a transfer memo.
+        #[pallet::constant]
+        type MaxMemoLength: Get<u32>;
+
+        /// Number of recent memo transfers kept per recipient.
+        #[pallet::constant]
+        type MaxRecentTransfers: Get<u32>;
+
+        /// Maximum number of vesting schedules per account.
+        #[pallet::constant]
+        type MaxVestingSchedules: Get<u32>;
+
+        /// Minimum amount that can be transferred with a vesting schedule.
+        #[pallet::constant]
+        type MinVestedTransfer: Get<u128>;
+
+        /// Maximum number of hashes in an airdrop merkle proof.
+        #[pallet::constant]
+        type MaxProofLength: Get<u32>;
+
+        /// Priority of unsigned airdrop claim transactions.
+        #[pallet::constant]
+        type AirdropUnsignedPriority: Get<TransactionPriority>;
+
+        /// Origin allowed to freeze accounts and manage the blocklist.
+        type ComplianceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum length of a freeze or blocklist reason.
+        #[pallet::constant]
+        type MaxReasonLength: Get<u32>;
+
+        /// Origin allowed to pause and unpause calls.
+        type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Number of blocks after which a pause expires unless renewed.
+        #[pallet::constant]
+        type PauseDuration: Get<Self::BlockNumber>;
+
+        /// Sliding window, in blocks, over which transfers are counted for the HFT tax.
+        #[pallet::constant]
+        type HftWindow: Get<Self::BlockNumber>;
+
+        /// Number of transfers allowed within `HftWindow` before the HFT tax applies.
+        #[pallet::constant]
+        type HftThreshold: Get<u32>;
+
+        /// Share of each transfer burned once an account exceeds `HftThreshold`.
+        #[pallet::constant]
+        type HftTaxRate: Get<Perbill>;
+
+        /// Length, in blocks, of the window over which outbound transfer volume is capped.
+        #[pallet::constant]
+        type OutflowWindow: Get<Self::BlockNumber>;
+
+        /// Maximum number of per-block outflow buckets kept per account; when full, the two
+        /// oldest buckets are merged into the later one.
+        #[pallet::constant]
+        type MaxOutflowEntries: Get<u32>;
+
+        /// Signature type used for off-chain signed permits.
+        type OffchainSignature: Verify<Signer = Self::SigningPublicKey> + Parameter;
+
+        /// Public key that signs permits, identifying the owner account.
+        type SigningPublicKey: IdentifyAccount<AccountId = Self::AccountId>;
+
+        /// Maximum number of call types a sponsor can restrict sponsorship to.
+        #[pallet::constant]
+        type MaxSponsoredCalls: Get<u32>;
+
+        /// Maximum number of open escrows an account can pay into.
+        #[pallet::constant]
+        type MaxEscrowsPerAccount: Get<u32>;
+
+        /// Maximum length of an HTLC preimage.
+        #[pallet::constant]
+        type MaxPreimageLength: Get<u32>;
+
+        /// Length, in blocks, of the period over which minter quotas apply.
+        #[pallet::constant]
+        type MintPeriod: Get<Self::BlockNumber>;
     }
 
     /// Token metadata
@@ -222,6 +240,325 @@ pub mod pallet {
         pub total_supply: u128,
         /// Circulating supply (total - burned)
         pub circulating_supply: u128,
+        /// Maximum supply that can ever be minted
+        pub max_supply: u128,
+    }
+
+    /// Distribution buckets from the tokenomics allocation table
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    pub enum AllocationBucket {
+        /// Global public airdrop for verified citizens
+        Airdrop,
+        /// VedGov to VedCoin government conversion
+        GovernmentConversion,
+        /// Validator and staking rewards
+        ValidatorRewards,
+        /// Startups, NGOs, DeFi apps and grants
+        Ecosystem,
+        /// Ved Foundation reserve
+        Foundation,
+        /// Audits, bug bounties and education
+        Security,
+    }
+
+    impl AllocationBucket {
+        /// The documented allocation table (sums to 100%)
+        pub fn default_allocations() -> Vec<(AllocationBucket, Perbill)> {
+            sp_std::vec![
+                (AllocationBucket::Airdrop, Perbill::from_percent(30)),
+                (AllocationBucket::GovernmentConversion, Perbill::from_percent(25)),
+                (AllocationBucket::ValidatorRewards, Perbill::from_percent(15)),
+                (AllocationBucket::Ecosystem, Perbill::from_percent(15)),
+                (AllocationBucket::Foundation, Perbill::from_percent(10)),
+                (AllocationBucket::Security, Perbill::from_percent(5)),
+            ]
+        }
+    }
+
+    /// Structured payment reference attached to a transfer
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum PaymentReference {
+        /// Merchant invoice identifier
+        Invoice([u8; 32]),
+        /// Order identifier
+        Order([u8; 32]),
+    }
+
+    /// Off-chain signed approval of `amount` for `spender`, valid until `deadline`
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct Permit<AccountId, BlockNumber> {
+        /// Account granting the allowance and signing the permit
+        pub owner: AccountId,
+        /// Account allowed to spend
+        pub spender: AccountId,
+        /// Allowance to set
+        pub amount: u128,
+        /// Must equal the owner's current permit nonce
+        pub nonce: u64,
+        /// Last block at which the permit can be submitted
+        pub deadline: BlockNumber,
+    }
+
+    /// A transfer carrying a memo, kept for payment reconciliation
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct MemoTransfer<AccountId, BlockNumber> {
+        /// Sender of the payment
+        pub from: AccountId,
+        /// Amount transferred
+        pub amount: u128,
+        /// Free-form memo
+        pub memo: Vec<u8>,
+        /// Optional typed reference (invoice id, order id)
+        pub reference: Option<PaymentReference>,
+        /// Block in which the transfer happened
+        pub block: BlockNumber,
+    }
+
+    /// Vesting schedule releasing locked tokens over time
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct VestingSchedule<BlockNumber> {
+        /// Total amount locked by this schedule
+        pub locked: u128,
+        /// Block at which vesting starts
+        pub start: BlockNumber,
+        /// Blocks after `start` before anything is released
+        pub cliff: BlockNumber,
+        /// Blocks after `start` until everything is released
+        pub duration: BlockNumber,
+        /// Release granularity in blocks (1 for linear release)
+        pub step: BlockNumber,
+    }
+
+    impl<BlockNumber: AtLeast32BitUnsigned + Copy> VestingSchedule<BlockNumber> {
+        /// Whether the schedule parameters are consistent
+        pub fn is_valid(&self) -> bool {
+            !self.locked.is_zero()
+                && !self.duration.is_zero()
+                && !self.step.is_zero()
+                && self.cliff <= self.duration
+        }
+
+        /// Amount released by block `n`
+        pub fn vested_at(&self, n: BlockNumber) -> u128 {
+            if n < self.start.saturating_add(self.cliff) {
+                return 0;
+            }
+
+            let elapsed = n.saturating_sub(self.start);
+            if elapsed >= self.duration {
+                return self.locked;
+            }
+
+            // Only whole steps are released
+            let step = self.step.max(One::one());
+            let released = elapsed - elapsed % step;
+            self.locked
+                .saturating_mul(released.saturated_into())
+                / self.duration.saturated_into::<u128>()
+        }
+
+        /// Amount still locked at block `n`
+        pub fn locked_at(&self, n: BlockNumber) -> u128 {
+            self.locked.saturating_sub(self.vested_at(n))
+        }
+    }
+
+    /// Airdrop campaign funded from the airdrop allocation bucket
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct AirdropCampaign<BlockNumber> {
+        /// Merkle root over `(account, amount)` leaves
+        pub merkle_root: H256,
+        /// Maximum amount that can be claimed in this campaign
+        pub cap: u128,
+        /// Amount claimed so far
+        pub claimed: u128,
+        /// Last block at which claims are accepted
+        pub deadline: BlockNumber,
+    }
+
+    /// Funds held from `payer` until released to `payee` or refunded
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Escrow<AccountId, BlockNumber> {
+        /// Account whose funds are held
+        pub payer: AccountId,
+        /// Account receiving the funds on release
+        pub payee: AccountId,
+        /// Account that can either release or refund
+        pub arbiter: AccountId,
+        /// Amount held
+        pub amount: u128,
+        /// Block from which the escrow is refunded automatically
+        pub deadline: BlockNumber,
+    }
+
+    /// Escrow as stored for a runtime
+    pub type EscrowOf<T> =
+        Escrow<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+    /// Continuous payment of `rate_per_block` from `sender` to `recipient` over `[start, stop)`
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct PaymentStream<AccountId, BlockNumber> {
+        /// Account funding the stream
+        pub sender: AccountId,
+        /// Account the stream pays
+        pub recipient: AccountId,
+        /// Amount accrued per block
+        pub rate_per_block: u128,
+        /// First block of accrual
+        pub start: BlockNumber,
+        /// Block at which accrual stops
+        pub stop: BlockNumber,
+        /// Amount already withdrawn by the recipient
+        pub withdrawn: u128,
+    }
+
+    impl<AccountId, BlockNumber: AtLeast32BitUnsigned + Copy> PaymentStream<AccountId, BlockNumber> {
+        /// Total deposit backing the stream
+        pub fn deposit(&self) -> u128 {
+            self.rate_per_block
+                .saturating_mul(self.stop.saturating_sub(self.start).saturated_into())
+        }
+
+        /// Amount accrued to the recipient by block `now`
+        pub fn accrued_at(&self, now: BlockNumber) -> u128 {
+            let elapsed = now.min(self.stop).saturating_sub(self.start);
+            self.rate_per_block.saturating_mul(elapsed.saturated_into())
+        }
+
+        /// Accrued amount the recipient has not withdrawn yet
+        pub fn withdrawable_at(&self, now: BlockNumber) -> u128 {
+            self.accrued_at(now).saturating_sub(self.withdrawn)
+        }
+    }
+
+    /// Minting rights of a registered minter
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct MinterQuota<BlockNumber> {
+        /// Maximum amount mintable per `MintPeriod`
+        pub quota: u128,
+        /// Amount minted in the current period
+        pub minted: u128,
+        /// First block of the current period
+        pub period_start: BlockNumber,
+    }
+
+    /// Hash function used for an HTLC hashlock
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum HashAlgorithm {
+        /// SHA-256, as used by Bitcoin HTLCs
+        Sha256,
+        /// BLAKE2b-256
+        Blake2_256,
+    }
+
+    /// Hash time-locked contract, keyed by its sender and hashlock
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct Htlc<AccountId, BlockNumber> {
+        /// Account whose funds are locked
+        pub sender: AccountId,
+        /// Account paid when the preimage is revealed
+        pub recipient: AccountId,
+        /// Amount locked
+        pub amount: u128,
+        /// Hash function the hashlock was computed with
+        pub algorithm: HashAlgorithm,
+        /// Block from which the sender can reclaim the funds
+        pub timelock: BlockNumber,
+    }
+
+    /// HTLC as stored for a runtime
+    pub type HtlcOf<T> =
+        Htlc<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::BlockNumber>;
+
+    /// Protocol fee charged on transfers: `flat + percentage * amount`
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct TransferFee {
+        /// Fixed part of the fee
+        pub flat: u128,
+        /// Share of the transferred amount
+        pub percentage: Perbill,
+    }
+
+    impl TransferFee {
+        /// Fee due on a transfer of `amount`
+        pub fn fee_for(&self, amount: u128) -> u128 {
+            self.flat.saturating_add(self.percentage.mul_floor(amount))
+        }
+    }
+
+    /// Optional anti-manipulation limits; `None` disables a limit
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct WhaleLimits {
+        /// Maximum share of the circulating supply a single account may hold
+        pub max_balance_share: Option<Perbill>,
+        /// Maximum amount an account may send within `OutflowWindow` blocks
+        pub max_outflow: Option<u128>,
+    }
+
+    /// Supply counters as recorded by the pallet next to the values derived from balances
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct SupplyReport {
+        /// `TokenInfo.circulating_supply`
+        pub recorded_circulating: u128,
+        /// Total issuance of the currency
+        pub actual_circulating: u128,
+        /// `TokenInfo.total_supply`
+        pub recorded_total: u128,
+        /// Total issuance plus `BurnedTokens`
+        pub actual_total: u128,
+        /// `TotalStaked`
+        pub recorded_staked: u128,
+        /// Sum of all stakes and delegations in `StakingInfoStorage`
+        pub actual_staked: u128,
+    }
+
+    impl SupplyReport {
+        /// Whether every recorded counter matches its actual value
+        pub fn is_consistent(&self) -> bool {
+            self.recorded_circulating == self.actual_circulating
+                && self.recorded_total == self.actual_total
+                && self.recorded_staked == self.actual_staked
+        }
+    }
+
+    /// Cause of a burn, recorded for supply analytics
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum BurnReason {
+        /// Transfer, HFT and transaction fees
+        Fee,
+        /// Slashed stake or held funds
+        Slash,
+        /// Unclaimed tokens of an expired campaign
+        Expiry,
+        /// Burned by the holder
+        Voluntary,
+        /// Burned when bridged out
+        Bridge,
+    }
+
+    /// Calls that can be paused individually
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub enum PausableCall {
+        /// Every transfer path
+        Transfer,
+        /// `stake`
+        Stake,
+        /// `unstake`
+        Unstake,
+        /// `mint_tokens`
+        Mint,
+        /// `burn_tokens` and `burn_from`
+        Burn,
+    }
+
+    /// Audit record for a frozen or blocklisted account
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    pub struct ComplianceRecord<BlockNumber> {
+        /// Reason given by the compliance origin
+        pub reason: Vec<u8>,
+        /// Block at which the restriction was applied
+        pub since: BlockNumber,
     }
 
     /// Staking information for an account
@@ -240,35 +577,343 @@ pub mod pallet {
     #[pallet::storage]
     #[pallet::getter(fn token_info)]
     /// Token metadata storage
-    pub type TokenInfoStorage<T: Config> = StorageValue<_, TokenInfo>;
+    pub type TokenInfoStorage<T: Config<I>, I: 'static = ()> = StorageValue<_, TokenInfo>;
 
     #[pallet::storage]
     #[pallet::getter(fn staking_info)]
     /// Staking information for accounts
-    pub type StakingInfoStorage<T: Config> = 
+    pub type StakingInfoStorage<T: Config<I>, I: 'static = ()> = 
         StorageMap<_, Blake2_128Concat, T::AccountId, StakingInfo<T::AccountId, u128>>;
 
     #[pallet::storage]
     #[pallet::getter(fn total_staked)]
     /// Total amount staked in the network
-    pub type TotalStaked<T: Config> = StorageValue<_, u128, ValueQuery>;
+    pub type TotalStaked<T: Config<I>, I: 'static = ()> = StorageValue<_, u128, ValueQuery>;
 
     #[pallet::storage]
     #[pallet::getter(fn burned_tokens)]
     /// Total amount of tokens burned
-    pub type BurnedTokens<T: Config> = StorageValue<_, u128, ValueQuery>;
+    pub type BurnedTokens<T: Config<I>, I: 'static = ()> = StorageValue<_, u128, ValueQuery>;
 
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config> {
-        /// Tokens were burned [amount]
-        TokensBurned { amount: u128 },
-        
-        /// Tokens were staked [validator, staker, amount]
-        TokensStaked {
-            validator: T::AccountId,
-            staker: T::AccountId,
-            amount: u128,
+    #[pallet::storage]
+    #[pallet::getter(fn burned_by_reason)]
+    /// Total amount of tokens burned per reason
+    pub type BurnedByReason<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, BurnReason, u128, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn block_fee_burn)]
+    /// Transaction fees burned in the current block
+    pub type BlockFeeBurn<T: Config<I>, I: 'static = ()> = StorageValue<_, u128, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn total_emitted)]
+    /// Total amount of tokens minted by the emission schedule
+    pub type TotalEmitted<T: Config<I>, I: 'static = ()> = StorageValue<_, u128, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn emission_start)]
+    /// First block of emission year 0; nothing is emitted until it is set
+    pub type EmissionStart<T: Config<I>, I: 'static = ()> = StorageValue<_, T::BlockNumber>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn allowance)]
+    /// Amount a spender may transfer on behalf of an owner [owner, spender]
+    pub type Allowances<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        u128,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn recent_transfers)]
+    /// Most recent memo transfers received by each account, oldest first
+    pub type RecentTransfers<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<MemoTransfer<T::AccountId, T::BlockNumber>, T::MaxRecentTransfers>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn vesting)]
+    /// Vesting schedules of each account
+    pub type Vesting<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<VestingSchedule<T::BlockNumber>, T::MaxVestingSchedules>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn airdrop_campaign)]
+    /// Registered airdrop campaigns
+    pub type AirdropCampaigns<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, u32, AirdropCampaign<T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_campaign_id)]
+    /// Identifier of the next airdrop campaign
+    pub type NextCampaignId<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn airdrop_claim)]
+    /// Amount claimed by each account per campaign [campaign_id, account]
+    pub type AirdropClaims<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, u32, Blake2_128Concat, T::AccountId, u128>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn airdrop_committed)]
+    /// Unclaimed amount reserved by active campaigns
+    pub type AirdropCommitted<T: Config<I>, I: 'static = ()> = StorageValue<_, u128, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn frozen_account)]
+    /// Accounts that may not send, stake or burn tokens
+    pub type FrozenAccounts<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ComplianceRecord<T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn blocklisted)]
+    /// Accounts that may neither send nor receive tokens
+    pub type Blocklist<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ComplianceRecord<T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn paused_until)]
+    /// Active pauses and the block at which they expire; `None` pauses the whole pallet
+    pub type Pauses<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, Option<PausableCall>, T::BlockNumber>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_fee)]
+    /// Protocol fee charged on every transfer
+    pub type TransferFeeStorage<T: Config<I>, I: 'static = ()> = StorageValue<_, TransferFee, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_activity)]
+    /// Blocks of an account's most recent transfers, used for the HFT tax
+    pub type TransferActivity<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<T::BlockNumber, T::HftThreshold>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    /// Accounts exempt from the HFT tax (market makers, bridge)
+    pub type HftExempt<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn whale_limits)]
+    /// Limits on balance concentration and outbound volume
+    pub type WhaleLimitsStorage<T: Config<I>, I: 'static = ()> = StorageValue<_, WhaleLimits, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn outflow)]
+    /// Amounts an account sent per block within the last `OutflowWindow` blocks
+    pub type Outflows<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(T::BlockNumber, u128), T::MaxOutflowEntries>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    /// Accounts exempt from the whale limits
+    pub type WhaleExempt<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn permit_nonce)]
+    /// Next permit nonce expected for each owner
+    pub type PermitNonces<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u64, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn sponsorship)]
+    /// Remaining fee quota a sponsor grants a beneficiary [sponsor, beneficiary]
+    pub type Sponsorships<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Blake2_128Concat,
+        T::AccountId,
+        u128,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn sponsored_calls)]
+    /// `(pallet index, call index)` pairs a sponsor pays for; empty means any call
+    pub type SponsoredCalls<T: Config<I>, I: 'static = ()> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(u8, u8), T::MaxSponsoredCalls>,
+        ValueQuery,
+    >;
+
+    #[pallet::storage]
+    #[pallet::getter(fn escrow)]
+    /// Open escrows by id
+    pub type Escrows<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, u32, Escrow<T::AccountId, T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_escrow_id)]
+    /// Id assigned to the next escrow
+    pub type NextEscrowId<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    /// Open escrows an account is payer, payee or arbiter of [account, escrow id]
+    pub type EscrowsOf<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, u32, ()>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn escrow_count)]
+    /// Number of open escrows each account pays into
+    pub type EscrowCount<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::storage]
+    /// Open escrows bucketed by deadline [deadline, escrow id]
+    pub type EscrowDeadlines<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Twox64Concat, u32, ()>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn escrow_refund_cursor)]
+    /// Next deadline bucket to be processed by the automatic refund; set when the first
+    /// escrow is created
+    pub type EscrowRefundCursor<T: Config<I>, I: 'static = ()> =
+        StorageValue<_, T::BlockNumber, OptionQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn stream)]
+    /// Active payment streams by id
+    pub type Streams<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, u32, PaymentStream<T::AccountId, T::BlockNumber>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn next_stream_id)]
+    /// Id assigned to the next payment stream
+    pub type NextStreamId<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn htlc)]
+    /// Open hash time-locked contracts [sender, hashlock]
+    pub type Htlcs<T: Config<I>, I: 'static = ()> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Blake2_128Concat, H256, HtlcOf<T>>;
+
+    #[pallet::storage]
+    #[pallet::getter(fn minter)]
+    /// Accounts allowed to call `mint_tokens` and their quotas
+    pub type Minters<T: Config<I>, I: 'static = ()> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, MinterQuota<T::BlockNumber>>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        /// Token symbol; token info is only seeded when this is non-empty
+        pub symbol: Vec<u8>,
+        /// Token name
+        pub name: Vec<u8>,
+        /// Number of decimal places
+        pub decimals: u8,
+        /// Tokens minted at genesis and split across the allocation buckets
+        pub initial_supply: u128,
+        /// Share of the initial supply given to each bucket
+        pub allocations: Vec<(AllocationBucket, Perbill)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            Self {
+                symbol: Vec::new(),
+                name: Vec::new(),
+                decimals: 18,
+                initial_supply: 0,
+                allocations: AllocationBucket::default_allocations(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig {
+        fn build(&self) {
+            assert!(
+                self.initial_supply <= T::MaxSupply::get(),
+                "Initial supply exceeds the maximum supply"
+            );
+            assert!(
+                self.symbol.len() <= T::MaxSymbolLength::get() as usize,
+                "Token symbol is too long"
+            );
+
+            let mut buckets = Vec::with_capacity(self.allocations.len());
+            let mut total_parts: u64 = 0;
+            for (bucket, share) in &self.allocations {
+                assert!(!buckets.contains(bucket), "Duplicate allocation bucket");
+                buckets.push(*bucket);
+                total_parts = total_parts.saturating_add(share.deconstruct().into());
+            }
+            if !self.allocations.is_empty() || !self.initial_supply.is_zero() {
+                assert!(
+                    total_parts == u64::from(Perbill::one().deconstruct()),
+                    "Allocation percentages must sum to 100%"
+                );
+            }
+
+            let mut allocated: u128 = 0;
+            for (index, (bucket, share)) in self.allocations.iter().enumerate() {
+                // The last bucket picks up any rounding remainder
+                let amount = if index + 1 == self.allocations.len() {
+                    self.initial_supply.saturating_sub(allocated)
+                } else {
+                    share.mul_floor(self.initial_supply)
+                };
+                allocated = allocated.saturating_add(amount);
+                let _ = T::Currency::deposit_creating(
+                    &Pallet::<T, I>::bucket_account(*bucket),
+                    amount.saturated_into(),
+                );
+            }
+
+            // Block 0 is genesis, so emission starts at block 1
+            EmissionStart::<T, I>::put(T::BlockNumber::one());
+
+            if !self.symbol.is_empty() {
+                TokenInfoStorage::<T, I>::put(TokenInfo {
+                    symbol: self.symbol.clone(),
+                    name: self.name.clone(),
+                    decimals: self.decimals,
+                    total_supply: self.initial_supply,
+                    circulating_supply: self.initial_supply,
+                    max_supply: T::MaxSupply::get(),
+                });
+            }
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config<I>, I: 'static = ()> {
+        /// Tokens were burned [amount, reason]
+        TokensBurned { amount: u128, reason: BurnReason },
+        
+        /// Tokens were staked [validator, staker, amount]
+        TokensStaked {
+            validator: T::AccountId,
+            staker: T::AccountId,
+            amount: u128,
         },
         
         /// Tokens were unstaked [validator, staker, amount]
@@ -278,6 +923,13 @@ pub mod pallet {
             amount: u128,
         },
         
+        /// Staked tokens were slashed and burned [validator, staker, amount]
+        StakeSlashed {
+            validator: T::AccountId,
+            staker: T::AccountId,
+            amount: u128,
+        },
+
         /// Validator was registered [account, commission]
         ValidatorRegistered {
             account: T::AccountId,
@@ -297,15 +949,244 @@ pub mod pallet {
             amount: u128,
         },
 
-        /// Tokens minted [to, amount]
+        /// Tokens minted [minter, to, amount]; `minter` is `None` for mints through the
+        /// `fungible` traits
         TokensMinted {
+            minter: Option<T::AccountId>,
+            to: T::AccountId,
+            amount: u128,
+        },
+
+        /// Tokens transferred with a memo [from, to, amount, memo, reference]
+        TokensTransferredWithMemo {
+            from: T::AccountId,
+            to: T::AccountId,
+            amount: u128,
+            memo: Vec<u8>,
+            reference: Option<PaymentReference>,
+        },
+
+        /// Tokens transferred under a vesting schedule [from, to, locked]
+        VestingScheduleAdded {
+            from: T::AccountId,
             to: T::AccountId,
+            locked: u128,
+        },
+
+        /// Vesting lock updated [who, unvested]
+        VestingUpdated {
+            who: T::AccountId,
+            unvested: u128,
+        },
+
+        /// Vesting schedule removed by governance [who, index]
+        VestingScheduleRemoved {
+            who: T::AccountId,
+            index: u32,
+        },
+
+        /// Airdrop campaign registered [campaign_id, merkle_root, cap, deadline]
+        AirdropRegistered {
+            campaign_id: u32,
+            merkle_root: H256,
+            cap: u128,
+            deadline: T::BlockNumber,
+        },
+
+        /// Airdrop claimed [campaign_id, who, amount]
+        AirdropClaimed {
+            campaign_id: u32,
+            who: T::AccountId,
+            amount: u128,
+        },
+
+        /// Airdrop campaign closed and unclaimed tokens burned [campaign_id, burned]
+        AirdropExpired {
+            campaign_id: u32,
+            burned: u128,
+        },
+
+        /// Account frozen by compliance [who, reason]
+        AccountFrozen {
+            who: T::AccountId,
+            reason: Vec<u8>,
+        },
+
+        /// Account unfrozen by compliance [who]
+        AccountUnfrozen { who: T::AccountId },
+
+        /// Account added to the blocklist [who, reason]
+        AccountBlocklisted {
+            who: T::AccountId,
+            reason: Vec<u8>,
+        },
+
+        /// Account removed from the blocklist [who]
+        AccountUnblocklisted { who: T::AccountId },
+
+        /// Call paused until the given block; `None` is the whole pallet [call, until]
+        Paused {
+            call: Option<PausableCall>,
+            until: T::BlockNumber,
+        },
+
+        /// Pause lifted; `None` is the whole pallet [call]
+        Unpaused { call: Option<PausableCall> },
+
+        /// Protocol transfer fee updated [fee]
+        TransferFeeSet { fee: TransferFee },
+
+        /// Protocol fee charged on a transfer [who, burned, to_treasury]
+        FeeCharged {
+            who: T::AccountId,
+            burned: u128,
+            to_treasury: u128,
+        },
+
+        /// HFT exemption granted or revoked [who, exempt]
+        HftExemptionSet { who: T::AccountId, exempt: bool },
+
+        /// HFT micro-tax burned from a transfer [who, amount]
+        HftTaxCollected { who: T::AccountId, amount: u128 },
+
+        /// Whale limits updated [limits]
+        WhaleLimitsSet { limits: WhaleLimits },
+
+        /// Whale limit exemption granted or revoked [who, exempt]
+        WhaleExemptionSet { who: T::AccountId, exempt: bool },
+
+        /// Sponsorship deposit changed [sponsor, deposit]
+        SponsorDepositUpdated { sponsor: T::AccountId, deposit: u128 },
+
+        /// Beneficiary fee quota set [sponsor, beneficiary, quota]
+        SponsorshipSet {
+            sponsor: T::AccountId,
+            beneficiary: T::AccountId,
+            quota: u128,
+        },
+
+        /// Call types covered by a sponsor updated [sponsor, calls]
+        SponsoredCallsSet { sponsor: T::AccountId, calls: Vec<(u8, u8)> },
+
+        /// Transaction fee paid by a sponsor [sponsor, beneficiary, fee]
+        FeeSponsored {
+            sponsor: T::AccountId,
+            beneficiary: T::AccountId,
+            fee: u128,
+        },
+
+        /// Sponsor's deposit or the beneficiary's quota can no longer cover the last fee [sponsor, beneficiary]
+        SponsorshipExhausted { sponsor: T::AccountId, beneficiary: T::AccountId },
+
+        /// Escrow opened [escrow_id, payer, payee, arbiter, amount, deadline]
+        EscrowCreated {
+            escrow_id: u32,
+            payer: T::AccountId,
+            payee: T::AccountId,
+            arbiter: T::AccountId,
+            amount: u128,
+            deadline: T::BlockNumber,
+        },
+
+        /// Escrowed funds paid to the payee [escrow_id, payee, amount]
+        EscrowReleased {
+            escrow_id: u32,
+            payee: T::AccountId,
+            amount: u128,
+        },
+
+        /// Escrowed funds returned to the payer [escrow_id, payer, amount]
+        EscrowRefunded {
+            escrow_id: u32,
+            payer: T::AccountId,
+            amount: u128,
+        },
+
+        /// Payment stream created [stream_id, sender, recipient, rate_per_block, start, stop]
+        StreamCreated {
+            stream_id: u32,
+            sender: T::AccountId,
+            recipient: T::AccountId,
+            rate_per_block: u128,
+            start: T::BlockNumber,
+            stop: T::BlockNumber,
+        },
+
+        /// Accrued stream funds withdrawn [stream_id, recipient, amount]
+        StreamWithdrawn {
+            stream_id: u32,
+            recipient: T::AccountId,
+            amount: u128,
+        },
+
+        /// Payment stream cancelled [stream_id, paid_to_recipient, refunded_to_sender]
+        StreamCancelled {
+            stream_id: u32,
+            paid_to_recipient: u128,
+            refunded_to_sender: u128,
+        },
+
+        /// Funds locked in an HTLC [hashlock, sender, recipient, amount, algorithm, timelock]
+        HtlcLocked {
+            hashlock: H256,
+            sender: T::AccountId,
+            recipient: T::AccountId,
+            amount: u128,
+            algorithm: HashAlgorithm,
+            timelock: T::BlockNumber,
+        },
+
+        /// HTLC claimed with its preimage [hashlock, sender, recipient, amount, preimage]
+        HtlcClaimed {
+            hashlock: H256,
+            sender: T::AccountId,
+            recipient: T::AccountId,
+            amount: u128,
+            preimage: Vec<u8>,
+        },
+
+        /// Expired HTLC refunded to its sender [hashlock, sender, amount]
+        HtlcRefunded {
+            hashlock: H256,
+            sender: T::AccountId,
+            amount: u128,
+        },
+
+        /// Minter registered [minter, quota]
+        MinterAdded { minter: T::AccountId, quota: u128 },
+
+        /// Minter removed [minter]
+        MinterRemoved { minter: T::AccountId },
+
+        /// Minter quota changed [minter, quota]
+        MinterQuotaSet { minter: T::AccountId, quota: u128 },
+
+        /// Supply counters recomputed; the report holds the values before and after [report]
+        SupplyReconciled { report: SupplyReport },
+
+        /// Batch of transfers applied [from, recipients, total]
+        BatchTransferred {
+            from: T::AccountId,
+            recipients: u32,
+            total: u128,
+        },
+
+        /// Spending allowance set [owner, spender, amount]
+        Approval {
+            owner: T::AccountId,
+            spender: T::AccountId,
             amount: u128,
         },
+
+        /// Scheduled emission minted into the pallet account [amount, year]
+        EmissionMinted {
+            amount: u128,
+            year: u32,
+        },
     }
 
     #[pallet::error]
-    pub enum Error<T> {
+    pub enum Error<T, I = ()> {
         /// Token info already initialized
         TokenAlreadyInitialized,
         /// Token info not found
@@ -320,11 +1201,182 @@ pub mod pallet {
         SymbolTooLong,
         /// Cannot stake zero amount
         ZeroStake,
+        /// Minting would push the total supply above the maximum supply
+        MaxSupplyExceeded,
+        /// Spender allowance is lower than the requested amount
+        InsufficientAllowance,
+        /// Batch transfer has no recipients
+        EmptyBatch,
+        /// Free balance does not cover the requested amount
+        InsufficientBalance,
+        /// Vesting schedule parameters are invalid
+        InvalidVestingSchedule,
+        /// Vested transfer amount is below the minimum
+        AmountLow,
+        /// Account already has the maximum number of vesting schedules
+        TooManyVestingSchedules,
+        /// Account has no vesting schedule at the given index
+        NotVesting,
+        /// Airdrop campaign does not exist
+        AirdropNotFound,
+        /// Airdrop campaign deadline has passed
+        AirdropExpired,
+        /// Airdrop campaign deadline has not passed yet
+        AirdropNotExpired,
+        /// Account already claimed from this campaign
+        AlreadyClaimed,
+        /// Merkle proof does not match the campaign root
+        InvalidMerkleProof,
+        /// Claim would exceed the campaign cap
+        AirdropCapExceeded,
+        /// Deadline must be in the future
+        InvalidDeadline,
+        /// Account is frozen
+        AccountFrozen,
+        /// Account is on the blocklist
+        AccountBlocklisted,
+        /// Account is not frozen
+        NotFrozen,
+        /// Account is not on the blocklist
+        NotBlocklisted,
+        /// Call is paused
+        CallPaused,
+        /// Call is not paused
+        NotPaused,
+        /// Account would hold more than the allowed share of the circulating supply
+        BalanceShareExceeded,
+        /// Account would exceed its outbound transfer limit for the current window
+        OutflowLimitExceeded,
+        /// Permit deadline has passed
+        PermitExpired,
+        /// Permit nonce does not match the owner's next nonce
+        InvalidPermitNonce,
+        /// Permit signature does not match the owner
+        InvalidSignature,
+        /// Escrow does not exist
+        EscrowNotFound,
+        /// Caller may not settle this escrow
+        NotEscrowParty,
+        /// Account pays into too many open escrows
+        TooManyEscrows,
+        /// Stream must have a non-zero rate and `now <= start < stop`
+        InvalidStream,
+        /// Stream does not exist
+        StreamNotFound,
+        /// Caller is not the sender or recipient of the stream
+        NotStreamParty,
+        /// Nothing has accrued since the last withdrawal
+        NothingToWithdraw,
+        /// An HTLC with this hashlock already exists
+        HtlcExists,
+        /// No HTLC matches the hashlock or preimage
+        HtlcNotFound,
+        /// HTLC timelock has passed
+        HtlcExpired,
+        /// HTLC timelock has not passed yet
+        HtlcNotExpired,
+        /// Account is already a minter
+        AlreadyMinter,
+        /// Account is not a minter
+        NotMinter,
+        /// Mint would exceed the minter's quota for the current period
+        MintQuotaExceeded,
+        /// Burns through `burn_from` must be `Voluntary` or `Bridge`
+        InvalidBurnReason,
+        /// Fewer validators given than are stored in `StakingInfoStorage`
+        ValidatorWitnessTooLow,
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config<I>, I: 'static> ValidateUnsigned for Pallet<T, I> {
+        type Call = Call<T, I>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            if let Call::claim_airdrop { campaign_id, who, amount, proof } = call {
+                Self::ensure_pallet_not_paused().map_err(|_| InvalidTransaction::Call)?;
+                Self::ensure_can_receive(who).map_err(|_| InvalidTransaction::BadSigner)?;
+                let campaign = Self::validate_claim(*campaign_id, who, *amount, proof).map_err(
+                    |error| match error {
+                        Error::<T, I>::AirdropExpired | Error::<T, I>::AlreadyClaimed => {
+                            InvalidTransaction::Stale
+                        },
+                        Error::<T, I>::InvalidMerkleProof => InvalidTransaction::BadProof,
+                        Error::<T, I>::AirdropCapExceeded => InvalidTransaction::ExhaustsResources,
+                        _ => InvalidTransaction::Call,
+                    },
+                )?;
+
+                let now = frame_system::Pallet::<T>::block_number();
+                ValidTransaction::with_tag_prefix("VedCoinAirdrop")
+                    .priority(T::AirdropUnsignedPriority::get())
+                    .and_provides((campaign_id, who))
+                    .longevity(campaign.deadline.saturating_sub(now).saturated_into::<u64>().max(1))
+                    .propagate(true)
+                    .build()
+            } else {
+                InvalidTransaction::Call.into()
+            }
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+        fn on_initialize(n: T::BlockNumber) -> Weight {
+            BlockFeeBurn::<T, I>::kill();
+
+            let scheduled = Self::emission_at(n);
+            if scheduled.is_zero() {
+                return T::DbWeight::get().writes(1);
+            }
+
+            // Never emit past the maximum supply
+            let amount = scheduled.min(Self::mintable_supply());
+            if amount.is_zero() {
+                return T::DbWeight::get().reads_writes(1, 1);
+            }
+
+            if let Ok(minted) = Self::do_mint(&Self::account_id(), amount) {
+                TotalEmitted::<T, I>::put(Self::total_emitted().saturating_add(minted));
+                Self::deposit_event(Event::EmissionMinted {
+                    amount: minted,
+                    year: Self::emission_year(n).saturated_into(),
+                });
+            }
+
+            T::DbWeight::get().reads_writes(3, 4)
+        }
+
+        fn on_idle(n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            Self::refund_expired_escrows(n, remaining_weight)
+        }
+
+        /// Check the supply counters against `Config::Currency`
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: T::BlockNumber) -> Result<(), &'static str> {
+            let report = Self::supply_report();
+            if Self::token_info().is_some() {
+                ensure!(
+                    report.recorded_circulating == report.actual_circulating,
+                    "circulating supply does not match total issuance"
+                );
+                ensure!(
+                    report.recorded_total == report.actual_total,
+                    "total supply does not match circulating supply plus burned tokens"
+                );
+            }
+            ensure!(
+                report.recorded_staked == report.actual_staked,
+                "total staked does not match the sum of stakes"
+            );
+            Ok(())
+        }
     }
 
     #[pallet::call]
-    impl<T: Config> Pallet<T> {
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
         /// Initialize token information (can only be called once)
+        ///
+        /// The supply is taken from the currency's total issuance and the tokens burned so far.
         #[pallet::weight(10_000)]
         #[pallet::call_index(0)]
         pub fn initialize_token(
@@ -332,18 +1384,24 @@ pub mod pallet {
             symbol: Vec<u8>,
             name: Vec<u8>,
             decimals: u8,
-            total_supply: u128,
         ) -> DispatchResult {
             ensure_root(origin)?;
 
             ensure!(
                 symbol.len() <= T::MaxSymbolLength::get() as usize,
-                Error::<T>::SymbolTooLong
+                Error::<T, I>::SymbolTooLong
+            );
+
+            ensure!(
+                !TokenInfoStorage::<T, I>::exists(),
+                Error::<T, I>::TokenAlreadyInitialized
             );
 
+            let circulating_supply: u128 = T::Currency::total_issuance().saturated_into();
+            let total_supply = circulating_supply.saturating_add(Self::burned_tokens());
             ensure!(
-                !TokenInfoStorage::<T>::exists(),
-                Error::<T>::TokenAlreadyInitialized
+                total_supply <= T::MaxSupply::get(),
+                Error::<T, I>::MaxSupplyExceeded
             );
 
             let token_info = TokenInfo {
@@ -351,10 +1409,11 @@ pub mod pallet {
                 name,
                 decimals,
                 total_supply,
-                circulating_supply: total_supply,
+                circulating_supply,
+                max_supply: T::MaxSupply::get(),
             };
 
-            TokenInfoStorage::<T>::put(&token_info);
+            TokenInfoStorage::<T, I>::put(&token_info);
 
             Ok(())
         }
@@ -367,14 +1426,14 @@ pub mod pallet {
             to: T::AccountId,
             amount: u128,
         ) -> DispatchResult {
-            let from = ensure_signed(origin)?;
-            ensure!(amount > 0, Error::<T>::ZeroStake);
-            T::Currency::transfer(&from, &to, amount, frame_support::traits::ExistenceRequirement::AllowDeath)?;
-            Self::deposit_event(Event::TokensTransferred { from: from.clone(), to: to.clone(), amount });
+            let from = T::TransferOrigin::ensure_origin(origin)?;
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            Self::do_transfer(&from, &to, amount)?;
+            Self::deposit_event(Event::TokensTransferred { from, to, amount });
             Ok(())
         }
 
-        /// Mint new tokens (root only, for controlled supply increases)
+        /// Mint new tokens within the caller's minter quota
         #[pallet::weight(10_000)]
         #[pallet::call_index(98)]
         pub fn mint_tokens(
@@ -382,16 +1441,13 @@ pub mod pallet {
             to: T::AccountId,
             amount: u128,
         ) -> DispatchResult {
-            ensure_root(origin)?;
-            ensure!(amount > 0, Error::<T>::ZeroStake);
-            T::Currency::deposit_creating(&to, amount);
-            // Update token info
-            if let Some(mut token_info) = Self::token_info() {
-                token_info.circulating_supply = token_info.circulating_supply.saturating_add(amount);
-                token_info.total_supply = token_info.total_supply.saturating_add(amount);
-                TokenInfoStorage::<T>::put(&token_info);
-            }
-            Self::deposit_event(Event::TokensMinted { to: to.clone(), amount });
+            let minter = ensure_signed(origin)?;
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            Self::ensure_not_paused(PausableCall::Mint)?;
+            Self::use_mint_quota(&minter, amount)?;
+            let minted = Self::do_mint(&to, amount)?;
+            ensure!(!minted.is_zero(), Error::<T, I>::AmountLow);
+            Self::deposit_event(Event::TokensMinted { minter: Some(minter), to, amount: minted });
             Ok(())
         }
 
@@ -403,8 +1459,9 @@ pub mod pallet {
             commission: u8,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
 
-            ensure!(commission <= 100, Error::<T>::InvalidCommission);
+            ensure!(commission <= 100, Error::<T, I>::InvalidCommission);
 
             let staking_info = StakingInfo {
                 staked: 0u128,
@@ -413,7 +1470,7 @@ pub mod pallet {
                 is_validator: true,
             };
 
-            StakingInfoStorage::<T>::insert(&who, &staking_info);
+            StakingInfoStorage::<T, I>::insert(&who, &staking_info);
 
             Self::deposit_event(Event::ValidatorRegistered {
                 account: who,
@@ -433,17 +1490,19 @@ pub mod pallet {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            ensure!(amount > 0, Error::<T>::ZeroStake);
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            Self::ensure_not_paused(PausableCall::Stake)?;
+            Self::ensure_can_send(&who)?;
 
             // Ensure validator exists
             let mut validator_info = Self::staking_info(&validator)
-                .ok_or(Error::<T>::NotValidator)?;
+                .ok_or(Error::<T, I>::NotValidator)?;
 
-            ensure!(validator_info.is_validator, Error::<T>::NotValidator);
+            ensure!(validator_info.is_validator, Error::<T, I>::NotValidator);
 
-            // Reserve the tokens (this checks balance)
+            // Reserve the tokens under the staking id (this checks balance)
             let amount_to_reserve = amount.saturated_into();
-            T::Currency::reserve(&who, amount_to_reserve)?;
+            T::Currency::reserve_named(&STAKING_ID, &who, amount_to_reserve)?;
 
             // Update staking info
             if who == validator {
@@ -459,8 +1518,8 @@ pub mod pallet {
                 }
             }
 
-            StakingInfoStorage::<T>::insert(&validator, &validator_info);
-            TotalStaked::<T>::put(Self::total_staked().saturating_add(amount));
+            StakingInfoStorage::<T, I>::insert(&validator, &validator_info);
+            TotalStaked::<T, I>::put(Self::total_staked().saturating_add(amount));
 
             Self::deposit_event(Event::TokensStaked {
                 validator,
@@ -481,10 +1540,11 @@ pub mod pallet {
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            ensure!(amount > 0, Error::<T>::ZeroStake);
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            Self::ensure_not_paused(PausableCall::Unstake)?;
 
             let mut validator_info = Self::staking_info(&validator)
-                .ok_or(Error::<T>::NotValidator)?;
+                .ok_or(Error::<T, I>::NotValidator)?;
 
             let amount_to_unreserve = amount.saturated_into();
 
@@ -492,7 +1552,7 @@ pub mod pallet {
                 // Self-unstaking
                 ensure!(
                     validator_info.staked >= amount,
-                    Error::<T>::InsufficientStake
+                    Error::<T, I>::InsufficientStake
                 );
                 validator_info.staked = validator_info.staked.saturating_sub(amount);
             } else {
@@ -500,7 +1560,7 @@ pub mod pallet {
                 if let Some(pos) = validator_info.delegators.iter().position(|(acc, _)| acc == &who) {
                     ensure!(
                         validator_info.delegators[pos].1 >= amount,
-                        Error::<T>::InsufficientStake
+                        Error::<T, I>::InsufficientStake
                     );
                     validator_info.delegators[pos].1 = 
                         validator_info.delegators[pos].1.saturating_sub(amount);
@@ -510,15 +1570,17 @@ pub mod pallet {
                         validator_info.delegators.remove(pos);
                     }
                 } else {
-                    return Err(Error::<T>::InsufficientStake.into());
+                    return Err(Error::<T, I>::InsufficientStake.into());
                 }
             }
 
-            // Unreserve the tokens
-            T::Currency::unreserve(&who, amount_to_unreserve);
+            // Unreserve the tokens; only funds reserved for staking can be released
+            let missing = T::Currency::unreserve_named(&STAKING_ID, &who, amount_to_unreserve);
+            ensure!(missing.is_zero(), Error::<T, I>::InsufficientStake);
+            Self::ensure_within_balance_share(&who)?;
 
-            StakingInfoStorage::<T>::insert(&validator, &validator_info);
-            TotalStaked::<T>::put(Self::total_staked().saturating_sub(amount));
+            StakingInfoStorage::<T, I>::insert(&validator, &validator_info);
+            TotalStaked::<T, I>::put(Self::total_staked().saturating_sub(amount));
 
             Self::deposit_event(Event::TokensUnstaked {
                 validator,
@@ -534,84 +1596,3476 @@ pub mod pallet {
         #[pallet::call_index(4)]
         pub fn burn_tokens(origin: OriginFor<T>, amount: u128) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            Self::ensure_not_paused(PausableCall::Burn)?;
+            Self::ensure_can_send(&who)?;
 
             // Burn tokens from the caller's account
-            let amount_to_burn = amount.saturated_into();
-            T::Currency::withdraw(
-                &who,
-                amount_to_burn,
-                frame_support::traits::WithdrawReasons::all(),
-                frame_support::traits::ExistenceRequirement::AllowDeath,
-            )?;
+            Self::do_burn(&who, amount, BurnReason::Voluntary)
+        }
 
-            // Update token info
-            if let Some(mut token_info) = Self::token_info() {
-                token_info.circulating_supply = 
-                    token_info.circulating_supply.saturating_sub(amount);
-                TokenInfoStorage::<T>::put(&token_info);
-            }
+        /// Recompute the supply and staking counters from balances and stakes
+        ///
+        /// Emits the counters before and after so that any drift can be audited. `validators`
+        /// must be at least the number of `StakingInfoStorage` entries, which the call is
+        /// weighed by.
+        #[pallet::weight(10_000u64.saturating_add(10_000u64.saturating_mul(*validators as u64)))]
+        #[pallet::call_index(45)]
+        pub fn reconcile_supply(origin: OriginFor<T>, validators: u32) -> DispatchResult {
+            ensure_root(origin)?;
 
-            BurnedTokens::<T>::put(Self::burned_tokens().saturating_add(amount));
+            // Read at most one entry more than paid for, to tell whether the witness is too low
+            let mut counted: u32 = 0;
+            let report = Self::report_from_stakes(
+                StakingInfoStorage::<T, I>::iter_values()
+                    .take((validators as usize).saturating_add(1))
+                    .inspect(|_| counted = counted.saturating_add(1)),
+            );
+            ensure!(counted <= validators, Error::<T, I>::ValidatorWitnessTooLow);
+            TokenInfoStorage::<T, I>::mutate(|maybe_info| {
+                if let Some(token_info) = maybe_info {
+                    token_info.circulating_supply = report.actual_circulating;
+                    token_info.total_supply = report.actual_total;
+                }
+            });
+            TotalStaked::<T, I>::put(report.actual_staked);
 
-            Self::deposit_event(Event::TokensBurned { amount });
+            Self::deposit_event(Event::SupplyReconciled { report });
 
             Ok(())
         }
-    }
 
-    impl<T: Config> Pallet<T> {
-        /// Get the account ID of the pallet
-        pub fn account_id() -> T::AccountId {
-            T::PalletId::get().into_account_truncating()
-        }
+        /// Burn tokens from `owner`, spending the caller's allowance
+        ///
+        /// `reason` must be `Voluntary`, or `Bridge` when called through `BridgeOrigin`; the
+        /// other reasons are recorded by the pallet itself.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(44)]
+        pub fn burn_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            amount: u128,
+            reason: BurnReason,
+        ) -> DispatchResult {
+            let spender = match reason {
+                BurnReason::Voluntary => ensure_signed(origin)?,
+                BurnReason::Bridge => T::BridgeOrigin::ensure_origin(origin)?,
+                _ => return Err(Error::<T, I>::InvalidBurnReason.into()),
+            };
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            Self::ensure_not_paused(PausableCall::Burn)?;
+            Self::ensure_can_send(&spender)?;
+            Self::ensure_can_send(&owner)?;
 
-        /// Calculate and distribute staking rewards
-        pub fn distribute_rewards(validator: &T::AccountId, total_rewards: u128) -> DispatchResult {
-            if let Some(validator_info) = Self::staking_info(validator) {
-                let commission_amount = total_rewards
-                    .saturating_mul(validator_info.commission as u128)
-                    .saturating_div(100);
-                
-                let delegator_rewards = total_rewards.saturating_sub(commission_amount);
-                let total_delegated: u128 = validator_info.delegators
-                    .iter()
-                    .map(|(_, amount)| *amount)
-                    .sum();
+            Self::spend_allowance(&owner, &spender, amount)?;
+            Self::do_burn(&owner, amount, reason)
+        }
 
-                // Distribute to delegators proportionally
-                for (delegator, stake) in &validator_info.delegators {
-                    if total_delegated > 0 {
-                        let delegator_reward = delegator_rewards
-                            .saturating_mul(*stake)
-                            .saturating_div(total_delegated);
-                        
-                        // Mint rewards to delegator
-                        let reward_amount = delegator_reward.saturated_into();
-                        let _ = T::Currency::deposit_creating(delegator, reward_amount);
-                    }
-                }
+        /// Allow `spender` to transfer up to `amount` of the caller's tokens
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(5)]
+        pub fn approve(
+            origin: OriginFor<T>,
+            spender: T::AccountId,
+            amount: u128,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+            Self::set_allowance(&owner, &spender, amount);
+            Ok(())
+        }
 
-                // Give commission to validator
-                let commission_balance = commission_amount.saturated_into();
-                let _ = T::Currency::deposit_creating(validator, commission_balance);
+        /// Transfer tokens from `owner` to `to`, spending the caller's allowance
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(6)]
+        pub fn transfer_from(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            to: T::AccountId,
+            amount: u128,
+        ) -> DispatchResult {
+            let spender = T::TransferOrigin::ensure_origin(origin)?;
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            Self::ensure_can_send(&spender)?;
+            Self::spend_allowance(&owner, &spender, amount)?;
+            Self::do_transfer(&owner, &to, amount)?;
+            Self::deposit_event(Event::TokensTransferred { from: owner, to, amount });
+            Ok(())
+        }
 
-                Self::deposit_event(Event::RewardsDistributed {
-                    validator: validator.clone(),
-                    total_rewards,
+        /// Increase the allowance granted to `spender` by `delta`
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(7)]
+        pub fn increase_allowance(
+            origin: OriginFor<T>,
+            spender: T::AccountId,
+            delta: u128,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+            let amount = Self::allowance(&owner, &spender).saturating_add(delta);
+            Self::set_allowance(&owner, &spender, amount);
+            Ok(())
+        }
+
+        /// Decrease the allowance granted to `spender` by `delta`
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(8)]
+        pub fn decrease_allowance(
+            origin: OriginFor<T>,
+            spender: T::AccountId,
+            delta: u128,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+            let amount = Self::allowance(&owner, &spender)
+                .checked_sub(delta)
+                .ok_or(Error::<T, I>::InsufficientAllowance)?;
+            Self::set_allowance(&owner, &spender, amount);
+            Ok(())
+        }
+
+        /// Transfer tokens to several recipients at once
+        ///
+        /// The whole batch is validated up front and either applies fully or not at all.
+        #[pallet::weight(10_000u64.saturating_add(10_000u64.saturating_mul(transfers.len() as u64)))]
+        #[pallet::call_index(9)]
+        pub fn transfer_batch(
+            origin: OriginFor<T>,
+            transfers: BoundedVec<(T::AccountId, u128), T::MaxBatchSize>,
+        ) -> DispatchResult {
+            let from = T::TransferOrigin::ensure_origin(origin)?;
+
+            ensure!(!transfers.is_empty(), Error::<T, I>::EmptyBatch);
+
+            let mut total: u128 = 0;
+            for (_, amount) in transfers.iter() {
+                ensure!(*amount > 0, Error::<T, I>::ZeroStake);
+                total = total
+                    .checked_add(*amount)
+                    .ok_or(Error::<T, I>::InsufficientBalance)?;
+            }
+            ensure!(
+                T::Currency::free_balance(&from).saturated_into::<u128>() >= total,
+                Error::<T, I>::InsufficientBalance
+            );
+
+            for (to, amount) in transfers.iter() {
+                Self::do_transfer(&from, to, *amount)?;
+            }
+
+            Self::deposit_event(Event::BatchTransferred {
+                from,
+                recipients: transfers.len() as u32,
+                total,
+            });
+
+            Ok(())
+        }
+
+        /// Transfer tokens with a memo and an optional payment reference
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(10)]
+        pub fn transfer_with_memo(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            amount: u128,
+            memo: BoundedVec<u8, T::MaxMemoLength>,
+            reference: Option<PaymentReference>,
+        ) -> DispatchResult {
+            let from = T::TransferOrigin::ensure_origin(origin)?;
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+
+            Self::do_transfer(&from, &to, amount)?;
+
+            let memo = memo.into_inner();
+            RecentTransfers::<T, I>::mutate(&to, |recent| {
+                // Drop the oldest entry once the buffer is full
+                if recent.is_full() {
+                    recent.remove(0);
+                }
+                let _ = recent.try_push(MemoTransfer {
+                    from: from.clone(),
+                    amount,
+                    memo: memo.clone(),
+                    reference: reference.clone(),
+                    block: frame_system::Pallet::<T>::block_number(),
                 });
+            });
+
+            Self::deposit_event(Event::TokensTransferredWithMemo {
+                from,
+                to,
+                amount,
+                memo,
+                reference,
+            });
+
+            Ok(())
+        }
+
+        /// Transfer tokens that unlock according to a vesting schedule
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(11)]
+        pub fn vested_transfer(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            schedule: VestingSchedule<T::BlockNumber>,
+        ) -> DispatchResult {
+            let from = T::TransferOrigin::ensure_origin(origin)?;
+
+            ensure!(schedule.is_valid(), Error::<T, I>::InvalidVestingSchedule);
+            ensure!(
+                schedule.locked >= T::MinVestedTransfer::get(),
+                Error::<T, I>::AmountLow
+            );
+
+            Vesting::<T, I>::try_mutate(&target, |schedules| {
+                schedules
+                    .try_push(schedule)
+                    .map_err(|_| Error::<T, I>::TooManyVestingSchedules)
+            })?;
+
+            Self::do_transfer(&from, &target, schedule.locked)?;
+            Self::update_vesting_lock(&target);
+
+            Self::deposit_event(Event::VestingScheduleAdded {
+                from,
+                to: target,
+                locked: schedule.locked,
+            });
+
+            Ok(())
+        }
+
+        /// Unlock any vested tokens of the caller
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(12)]
+        pub fn vest(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+            ensure!(Vesting::<T, I>::contains_key(&who), Error::<T, I>::NotVesting);
+            Self::update_vesting_lock(&who);
+            Ok(())
+        }
+
+        /// Remove a vesting schedule from an account (governance only)
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(13)]
+        pub fn force_remove_vesting(
+            origin: OriginFor<T>,
+            target: T::AccountId,
+            schedule_index: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Vesting::<T, I>::try_mutate(&target, |schedules| -> DispatchResult {
+                ensure!(
+                    (schedule_index as usize) < schedules.len(),
+                    Error::<T, I>::NotVesting
+                );
+                schedules.remove(schedule_index as usize);
+                Ok(())
+            })?;
+            Self::update_vesting_lock(&target);
+
+            Self::deposit_event(Event::VestingScheduleRemoved {
+                who: target,
+                index: schedule_index,
+            });
+
+            Ok(())
+        }
+
+        /// Register an airdrop campaign funded from the airdrop bucket
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(14)]
+        pub fn register_airdrop(
+            origin: OriginFor<T>,
+            merkle_root: H256,
+            cap: u128,
+            deadline: T::BlockNumber,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ensure!(cap > 0, Error::<T, I>::ZeroStake);
+            ensure!(
+                deadline > frame_system::Pallet::<T>::block_number(),
+                Error::<T, I>::InvalidDeadline
+            );
+
+            // Active campaigns can never promise more than the bucket holds
+            let committed = Self::airdrop_committed().saturating_add(cap);
+            let bucket = Self::bucket_account(AllocationBucket::Airdrop);
+            ensure!(
+                T::Currency::free_balance(&bucket).saturated_into::<u128>() >= committed,
+                Error::<T, I>::InsufficientBalance
+            );
+
+            let campaign_id = Self::next_campaign_id();
+            AirdropCampaigns::<T, I>::insert(
+                campaign_id,
+                AirdropCampaign { merkle_root, cap, claimed: 0, deadline },
+            );
+            NextCampaignId::<T, I>::put(campaign_id.saturating_add(1));
+            AirdropCommitted::<T, I>::put(committed);
+
+            Self::deposit_event(Event::AirdropRegistered {
+                campaign_id,
+                merkle_root,
+                cap,
+                deadline,
+            });
+
+            Ok(())
+        }
+
+        /// Claim an airdrop allocation with a merkle proof
+        ///
+        /// Submitted as an unsigned transaction so that accounts without VED can claim.
+        #[pallet::weight(10_000u64.saturating_add(1_000u64.saturating_mul(proof.len() as u64)))]
+        #[pallet::call_index(15)]
+        pub fn claim_airdrop(
+            origin: OriginFor<T>,
+            campaign_id: u32,
+            who: T::AccountId,
+            amount: u128,
+            proof: BoundedVec<H256, T::MaxProofLength>,
+        ) -> DispatchResult {
+            ensure_none(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            let mut campaign = Self::validate_claim(campaign_id, &who, amount, &proof)?;
+            Self::ensure_can_receive(&who)?;
+
+            T::Currency::transfer(
+                &Self::bucket_account(AllocationBucket::Airdrop),
+                &who,
+                amount.saturated_into(),
+                frame_support::traits::ExistenceRequirement::AllowDeath,
+            )?;
+
+            campaign.claimed = campaign.claimed.saturating_add(amount);
+            AirdropCampaigns::<T, I>::insert(campaign_id, campaign);
+            AirdropClaims::<T, I>::insert(campaign_id, &who, amount);
+            AirdropCommitted::<T, I>::mutate(|committed| *committed = committed.saturating_sub(amount));
+
+            Self::deposit_event(Event::AirdropClaimed { campaign_id, who, amount });
+
+            Ok(())
+        }
+
+        /// Close an expired airdrop campaign and burn its unclaimed tokens
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(16)]
+        pub fn expire_airdrop(origin: OriginFor<T>, campaign_id: u32) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            let campaign = Self::airdrop_campaign(campaign_id).ok_or(Error::<T, I>::AirdropNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() > campaign.deadline,
+                Error::<T, I>::AirdropNotExpired
+            );
+
+            let unclaimed = campaign.cap.saturating_sub(campaign.claimed);
+            if !unclaimed.is_zero() {
+                Self::do_burn(&Self::bucket_account(AllocationBucket::Airdrop), unclaimed, BurnReason::Expiry)?;
             }
 
+            AirdropCampaigns::<T, I>::remove(campaign_id);
+            AirdropCommitted::<T, I>::mutate(|committed| *committed = committed.saturating_sub(unclaimed));
+
+            Self::deposit_event(Event::AirdropExpired { campaign_id, burned: unclaimed });
+
             Ok(())
         }
-    }
-}
 
-// Runtime API for external queries
-sp_api::decl_runtime_apis! {
-    pub trait VedCoinApi<AccountId> {
-        fn get_token_info() -> Option<TokenInfo>;
-        fn get_staking_info(account: AccountId) -> Option<StakingInfo<AccountId, u128>>;
-        fn get_total_staked() -> u128;
-        fn get_burned_tokens() -> u128;
-    }
+        /// Freeze an account so it can no longer send, stake or burn tokens
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(17)]
+        pub fn freeze_account(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            reason: BoundedVec<u8, T::MaxReasonLength>,
+        ) -> DispatchResult {
+            T::ComplianceOrigin::ensure_origin(origin)?;
+
+            let reason = reason.into_inner();
+            FrozenAccounts::<T, I>::insert(
+                &who,
+                ComplianceRecord {
+                    reason: reason.clone(),
+                    since: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::AccountFrozen { who, reason });
+
+            Ok(())
+        }
+
+        /// Lift the freeze on an account
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(18)]
+        pub fn unfreeze_account(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::ComplianceOrigin::ensure_origin(origin)?;
+
+            ensure!(FrozenAccounts::<T, I>::contains_key(&who), Error::<T, I>::NotFrozen);
+            FrozenAccounts::<T, I>::remove(&who);
+
+            Self::deposit_event(Event::AccountUnfrozen { who });
+
+            Ok(())
+        }
+
+        /// Add an account to the blocklist so it can neither send nor receive tokens
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(19)]
+        pub fn add_to_blocklist(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            reason: BoundedVec<u8, T::MaxReasonLength>,
+        ) -> DispatchResult {
+            T::ComplianceOrigin::ensure_origin(origin)?;
+
+            let reason = reason.into_inner();
+            Blocklist::<T, I>::insert(
+                &who,
+                ComplianceRecord {
+                    reason: reason.clone(),
+                    since: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::AccountBlocklisted { who, reason });
+
+            Ok(())
+        }
+
+        /// Remove an account from the blocklist
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(20)]
+        pub fn remove_from_blocklist(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            T::ComplianceOrigin::ensure_origin(origin)?;
+
+            ensure!(Blocklist::<T, I>::contains_key(&who), Error::<T, I>::NotBlocklisted);
+            Blocklist::<T, I>::remove(&who);
+
+            Self::deposit_event(Event::AccountUnblocklisted { who });
+
+            Ok(())
+        }
+
+        /// Pause a call, or every user-facing call when `call` is `None`
+        ///
+        /// The pause expires after `PauseDuration` blocks; calling this again renews it.
+        /// Governance, compliance and pause calls stay available while the pallet is paused.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(21)]
+        pub fn pause(origin: OriginFor<T>, call: Option<PausableCall>) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            let until = frame_system::Pallet::<T>::block_number()
+                .saturating_add(T::PauseDuration::get());
+            Pauses::<T, I>::insert(call, until);
+
+            Self::deposit_event(Event::Paused { call, until });
+
+            Ok(())
+        }
+
+        /// Lift a pause before it expires
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(22)]
+        pub fn unpause(origin: OriginFor<T>, call: Option<PausableCall>) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?;
+
+            ensure!(Pauses::<T, I>::contains_key(call), Error::<T, I>::NotPaused);
+            Pauses::<T, I>::remove(call);
+
+            Self::deposit_event(Event::Unpaused { call });
+
+            Ok(())
+        }
+
+        /// Set the protocol fee charged on transfers
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(23)]
+        pub fn set_transfer_fee(
+            origin: OriginFor<T>,
+            flat: u128,
+            percentage: Perbill,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let fee = TransferFee { flat, percentage };
+            TransferFeeStorage::<T, I>::put(fee);
+
+            Self::deposit_event(Event::TransferFeeSet { fee });
+
+            Ok(())
+        }
+
+        /// Exempt an account from the HFT tax, or revoke its exemption
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(24)]
+        pub fn set_hft_exempt(origin: OriginFor<T>, who: T::AccountId, exempt: bool) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if exempt {
+                HftExempt::<T, I>::insert(&who, ());
+            } else {
+                HftExempt::<T, I>::remove(&who);
+                TransferActivity::<T, I>::remove(&who);
+            }
+
+            Self::deposit_event(Event::HftExemptionSet { who, exempt });
+
+            Ok(())
+        }
+
+        /// Set the whale limits; `None` disables a limit
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(25)]
+        pub fn set_whale_limits(
+            origin: OriginFor<T>,
+            max_balance_share: Option<Perbill>,
+            max_outflow: Option<u128>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            let limits = WhaleLimits { max_balance_share, max_outflow };
+            WhaleLimitsStorage::<T, I>::put(limits);
+
+            Self::deposit_event(Event::WhaleLimitsSet { limits });
+
+            Ok(())
+        }
+
+        /// Exempt an account from the whale limits, or revoke its exemption
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(26)]
+        pub fn set_whale_exempt(origin: OriginFor<T>, who: T::AccountId, exempt: bool) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if exempt {
+                WhaleExempt::<T, I>::insert(&who, ());
+            } else {
+                WhaleExempt::<T, I>::remove(&who);
+            }
+
+            Self::deposit_event(Event::WhaleExemptionSet { who, exempt });
+
+            Ok(())
+        }
+
+        /// Set an allowance from an off-chain signed permit; anyone can submit it
+        ///
+        /// The owner signs `(permit_domain_separator(), permit)` SCALE-encoded.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(27)]
+        pub fn permit(
+            origin: OriginFor<T>,
+            permit: Permit<T::AccountId, T::BlockNumber>,
+            signature: T::OffchainSignature,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(now <= permit.deadline, Error::<T, I>::PermitExpired);
+            let nonce = Self::permit_nonce(&permit.owner);
+            ensure!(permit.nonce == nonce, Error::<T, I>::InvalidPermitNonce);
+
+            let message = (Self::permit_domain_separator(), &permit).encode();
+            ensure!(
+                signature.verify(&message[..], &permit.owner),
+                Error::<T, I>::InvalidSignature
+            );
+
+            PermitNonces::<T, I>::insert(&permit.owner, nonce.saturating_add(1));
+            Self::set_allowance(&permit.owner, &permit.spender, permit.amount);
+
+            Ok(())
+        }
+
+        /// Reserve tokens to pay the fees of sponsored transactions
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(28)]
+        pub fn sponsor_deposit(origin: OriginFor<T>, amount: u128) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+
+            T::Currency::reserve_named(&SPONSORSHIP_ID, &sponsor, amount.saturated_into())?;
+
+            let deposit = T::Currency::reserved_balance_named(&SPONSORSHIP_ID, &sponsor).saturated_into();
+            Self::deposit_event(Event::SponsorDepositUpdated { sponsor, deposit });
+
+            Ok(())
+        }
+
+        /// Release tokens from the sponsorship deposit
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(29)]
+        pub fn sponsor_withdraw(origin: OriginFor<T>, amount: u128) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+
+            let missing = T::Currency::unreserve_named(&SPONSORSHIP_ID, &sponsor, amount.saturated_into());
+            ensure!(missing.is_zero(), Error::<T, I>::InsufficientBalance);
+
+            let deposit = T::Currency::reserved_balance_named(&SPONSORSHIP_ID, &sponsor).saturated_into();
+            Self::deposit_event(Event::SponsorDepositUpdated { sponsor, deposit });
+
+            Ok(())
+        }
+
+        /// Grant a beneficiary a fee quota; a zero quota revokes the sponsorship
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(30)]
+        pub fn set_sponsorship(
+            origin: OriginFor<T>,
+            beneficiary: T::AccountId,
+            quota: u128,
+        ) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            if quota.is_zero() {
+                Sponsorships::<T, I>::remove(&sponsor, &beneficiary);
+            } else {
+                Sponsorships::<T, I>::insert(&sponsor, &beneficiary, quota);
+            }
+
+            Self::deposit_event(Event::SponsorshipSet { sponsor, beneficiary, quota });
+
+            Ok(())
+        }
+
+        /// Restrict sponsorship to the given `(pallet index, call index)` pairs; empty allows any call
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(31)]
+        pub fn set_sponsored_calls(
+            origin: OriginFor<T>,
+            calls: BoundedVec<(u8, u8), T::MaxSponsoredCalls>,
+        ) -> DispatchResult {
+            let sponsor = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            SponsoredCalls::<T, I>::insert(&sponsor, &calls);
+
+            Self::deposit_event(Event::SponsoredCallsSet { sponsor, calls: calls.into_inner() });
+
+            Ok(())
+        }
+
+        /// Hold funds for `payee` until released by the payer or arbiter
+        ///
+        /// Escrows still open at `deadline` are refunded to the payer automatically. The transfer
+        /// policy, including the protocol fee, applies when the escrow is created, so a release
+        /// never depends on the payer's state.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(32)]
+        pub fn create_escrow(
+            origin: OriginFor<T>,
+            payee: T::AccountId,
+            amount: u128,
+            arbiter: T::AccountId,
+            deadline: T::BlockNumber,
+        ) -> DispatchResult {
+            let payer = T::TransferOrigin::ensure_origin(origin)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            ensure!(deadline > now, Error::<T, I>::InvalidDeadline);
+            Self::check_transfer(&payer, &payee, amount)?;
+
+            EscrowCount::<T, I>::try_mutate(&payer, |count| -> DispatchResult {
+                ensure!(*count < T::MaxEscrowsPerAccount::get(), Error::<T, I>::TooManyEscrows);
+                *count = count.saturating_add(1);
+                Ok(())
+            })?;
+            T::Currency::reserve_named(&ESCROW_ID, &payer, amount.saturated_into())?;
+            Self::charge_transfer(&payer, amount)?;
+
+            let escrow_id = Self::next_escrow_id();
+            let escrow = Escrow {
+                payer: payer.clone(),
+                payee: payee.clone(),
+                arbiter: arbiter.clone(),
+                amount,
+                deadline,
+            };
+            for party in [&payer, &payee, &arbiter] {
+                EscrowsOf::<T, I>::insert(party, escrow_id, ());
+            }
+            Escrows::<T, I>::insert(escrow_id, escrow);
+            EscrowDeadlines::<T, I>::insert(deadline, escrow_id, ());
+            NextEscrowId::<T, I>::put(escrow_id.saturating_add(1));
+            if !EscrowRefundCursor::<T, I>::exists() {
+                EscrowRefundCursor::<T, I>::put(now);
+            }
+
+            Self::deposit_event(Event::EscrowCreated {
+                escrow_id,
+                payer,
+                payee,
+                arbiter,
+                amount,
+                deadline,
+            });
+
+            Ok(())
+        }
+
+        /// Pay escrowed funds to the payee; callable by the payer or arbiter
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(33)]
+        pub fn release(origin: OriginFor<T>, escrow_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused(PausableCall::Transfer)?;
+
+            let escrow = Self::escrow(escrow_id).ok_or(Error::<T, I>::EscrowNotFound)?;
+            ensure!(
+                who == escrow.payer || who == escrow.arbiter,
+                Error::<T, I>::NotEscrowParty
+            );
+            Self::ensure_can_receive(&escrow.payee)?;
+
+            T::Currency::repatriate_reserved_named(
+                &ESCROW_ID,
+                &escrow.payer,
+                &escrow.payee,
+                escrow.amount.saturated_into(),
+                BalanceStatus::Free,
+            )?;
+            Self::close_escrow(escrow_id, &escrow);
+            Self::ensure_within_balance_share(&escrow.payee)?;
+
+            Self::deposit_event(Event::EscrowReleased {
+                escrow_id,
+                payee: escrow.payee,
+                amount: escrow.amount,
+            });
+
+            Ok(())
+        }
+
+        /// Return escrowed funds to the payer; callable by the payee or arbiter
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(34)]
+        pub fn refund(origin: OriginFor<T>, escrow_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            let escrow = Self::escrow(escrow_id).ok_or(Error::<T, I>::EscrowNotFound)?;
+            ensure!(
+                who == escrow.payee || who == escrow.arbiter,
+                Error::<T, I>::NotEscrowParty
+            );
+
+            Self::refund_escrow(escrow_id, escrow);
+
+            Ok(())
+        }
+
+        /// Stream `rate_per_block` to `recipient` from `start` until `stop`, reserving the
+        /// full amount up front
+        ///
+        /// The transfer policy, including the protocol fee, applies to the full amount when the
+        /// stream is created.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(35)]
+        pub fn create_stream(
+            origin: OriginFor<T>,
+            recipient: T::AccountId,
+            rate_per_block: u128,
+            start: T::BlockNumber,
+            stop: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = T::TransferOrigin::ensure_origin(origin)?;
+
+            ensure!(
+                rate_per_block > 0
+                    && start >= frame_system::Pallet::<T>::block_number()
+                    && stop > start,
+                Error::<T, I>::InvalidStream
+            );
+
+            let stream = PaymentStream {
+                sender: sender.clone(),
+                recipient: recipient.clone(),
+                rate_per_block,
+                start,
+                stop,
+                withdrawn: 0,
+            };
+            let deposit = stream.deposit();
+            Self::check_transfer(&sender, &recipient, deposit)?;
+            T::Currency::reserve_named(&STREAM_ID, &sender, deposit.saturated_into())?;
+            Self::settle_transfer(&sender, &recipient, deposit)?;
+
+            let stream_id = Self::next_stream_id();
+            Streams::<T, I>::insert(stream_id, stream);
+            NextStreamId::<T, I>::put(stream_id.saturating_add(1));
+
+            Self::deposit_event(Event::StreamCreated {
+                stream_id,
+                sender,
+                recipient,
+                rate_per_block,
+                start,
+                stop,
+            });
+
+            Ok(())
+        }
+
+        /// Withdraw everything accrued on a stream; callable by the recipient
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(36)]
+        pub fn withdraw_from_stream(origin: OriginFor<T>, stream_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused(PausableCall::Transfer)?;
+
+            let mut stream = Self::stream(stream_id).ok_or(Error::<T, I>::StreamNotFound)?;
+            ensure!(who == stream.recipient, Error::<T, I>::NotStreamParty);
+            Self::ensure_can_receive(&who)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let amount = stream.withdrawable_at(now);
+            ensure!(amount > 0, Error::<T, I>::NothingToWithdraw);
+
+            T::Currency::repatriate_reserved_named(
+                &STREAM_ID,
+                &stream.sender,
+                &stream.recipient,
+                amount.saturated_into(),
+                BalanceStatus::Free,
+            )?;
+            Self::ensure_within_balance_share(&who)?;
+            stream.withdrawn = stream.withdrawn.saturating_add(amount);
+
+            // A finished stream that has been fully paid out is removed
+            if stream.withdrawn >= stream.deposit() {
+                Streams::<T, I>::remove(stream_id);
+            } else {
+                Streams::<T, I>::insert(stream_id, &stream);
+            }
+
+            Self::deposit_event(Event::StreamWithdrawn {
+                stream_id,
+                recipient: who,
+                amount,
+            });
+
+            Ok(())
+        }
+
+        /// Cancel a stream, paying out what has accrued and refunding the rest to the sender
+        ///
+        /// If the recipient cannot be paid right now, for example because it is blocklisted or
+        /// at the balance share limit, the accrued part stays in the stream for the recipient
+        /// to withdraw later.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(37)]
+        pub fn cancel_stream(origin: OriginFor<T>, stream_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_not_paused(PausableCall::Transfer)?;
+
+            let mut stream = Self::stream(stream_id).ok_or(Error::<T, I>::StreamNotFound)?;
+            ensure!(
+                who == stream.sender || who == stream.recipient,
+                Error::<T, I>::NotStreamParty
+            );
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let withdrawable = stream.withdrawable_at(now);
+            let refunded_to_sender = stream.deposit().saturating_sub(stream.accrued_at(now));
+            T::Currency::unreserve_named(&STREAM_ID, &stream.sender, refunded_to_sender.saturated_into());
+
+            let paid = withdrawable > 0
+                && frame_support::storage::with_storage_layer(|| -> DispatchResult {
+                    Self::ensure_can_receive(&stream.recipient)?;
+                    T::Currency::repatriate_reserved_named(
+                        &STREAM_ID,
+                        &stream.sender,
+                        &stream.recipient,
+                        withdrawable.saturated_into(),
+                        BalanceStatus::Free,
+                    )?;
+                    Self::ensure_within_balance_share(&stream.recipient)
+                })
+                .is_ok();
+
+            let paid_to_recipient = if paid || withdrawable.is_zero() {
+                Streams::<T, I>::remove(stream_id);
+                withdrawable
+            } else {
+                // Stop accrual now and leave the accrued part for the recipient to withdraw
+                stream.stop = now.min(stream.stop);
+                Streams::<T, I>::insert(stream_id, &stream);
+                0
+            };
+
+            Self::deposit_event(Event::StreamCancelled {
+                stream_id,
+                paid_to_recipient,
+                refunded_to_sender,
+            });
+
+            Ok(())
+        }
+
+        /// Lock funds for `recipient` until the preimage of `hashlock` is revealed or
+        /// `timelock` is reached
+        ///
+        /// The transfer policy, including the protocol fee, applies when the HTLC is locked, so
+        /// a claim never depends on the sender's state once the preimage is public.
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(38)]
+        pub fn htlc_lock(
+            origin: OriginFor<T>,
+            recipient: T::AccountId,
+            amount: u128,
+            hashlock: H256,
+            algorithm: HashAlgorithm,
+            timelock: T::BlockNumber,
+        ) -> DispatchResult {
+            let sender = T::TransferOrigin::ensure_origin(origin)?;
+
+            ensure!(amount > 0, Error::<T, I>::ZeroStake);
+            ensure!(
+                timelock > frame_system::Pallet::<T>::block_number(),
+                Error::<T, I>::InvalidDeadline
+            );
+            ensure!(!Htlcs::<T, I>::contains_key(&sender, hashlock), Error::<T, I>::HtlcExists);
+            Self::check_transfer(&sender, &recipient, amount)?;
+
+            T::Currency::reserve_named(&HTLC_ID, &sender, amount.saturated_into())?;
+            Self::charge_transfer(&sender, amount)?;
+            Htlcs::<T, I>::insert(
+                &sender,
+                hashlock,
+                Htlc {
+                    sender: sender.clone(),
+                    recipient: recipient.clone(),
+                    amount,
+                    algorithm,
+                    timelock,
+                },
+            );
+
+            Self::deposit_event(Event::HtlcLocked {
+                hashlock,
+                sender,
+                recipient,
+                amount,
+                algorithm,
+                timelock,
+            });
+
+            Ok(())
+        }
+
+        /// Pay an HTLC of `sender` to its recipient by revealing the preimage; anyone can
+        /// submit it
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(39)]
+        pub fn htlc_claim(
+            origin: OriginFor<T>,
+            sender: T::AccountId,
+            preimage: BoundedVec<u8, T::MaxPreimageLength>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::ensure_not_paused(PausableCall::Transfer)?;
+
+            let (hashlock, htlc) =
+                Self::htlc_for_preimage(&sender, &preimage).ok_or(Error::<T, I>::HtlcNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < htlc.timelock,
+                Error::<T, I>::HtlcExpired
+            );
+            Self::ensure_can_receive(&htlc.recipient)?;
+
+            T::Currency::repatriate_reserved_named(
+                &HTLC_ID,
+                &htlc.sender,
+                &htlc.recipient,
+                htlc.amount.saturated_into(),
+                BalanceStatus::Free,
+            )?;
+            Htlcs::<T, I>::remove(&sender, hashlock);
+            Self::ensure_within_balance_share(&htlc.recipient)?;
+
+            Self::deposit_event(Event::HtlcClaimed {
+                hashlock,
+                sender: htlc.sender,
+                recipient: htlc.recipient,
+                amount: htlc.amount,
+                preimage: preimage.into_inner(),
+            });
+
+            Ok(())
+        }
+
+        /// Return an expired HTLC to its sender; anyone can submit it
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(40)]
+        pub fn htlc_refund(origin: OriginFor<T>, sender: T::AccountId, hashlock: H256) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::ensure_pallet_not_paused()?;
+
+            let htlc = Self::htlc(&sender, hashlock).ok_or(Error::<T, I>::HtlcNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= htlc.timelock,
+                Error::<T, I>::HtlcNotExpired
+            );
+
+            T::Currency::unreserve_named(&HTLC_ID, &htlc.sender, htlc.amount.saturated_into());
+            Htlcs::<T, I>::remove(&sender, hashlock);
+
+            Self::deposit_event(Event::HtlcRefunded {
+                hashlock,
+                sender: htlc.sender,
+                amount: htlc.amount,
+            });
+
+            Ok(())
+        }
+
+        /// Allow an account to mint up to `quota` per `MintPeriod`
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(41)]
+        pub fn add_minter(origin: OriginFor<T>, minter: T::AccountId, quota: u128) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(!Minters::<T, I>::contains_key(&minter), Error::<T, I>::AlreadyMinter);
+
+            Minters::<T, I>::insert(
+                &minter,
+                MinterQuota {
+                    quota,
+                    minted: 0,
+                    period_start: frame_system::Pallet::<T>::block_number(),
+                },
+            );
+
+            Self::deposit_event(Event::MinterAdded { minter, quota });
+
+            Ok(())
+        }
+
+        /// Revoke an account's minting rights
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(42)]
+        pub fn remove_minter(origin: OriginFor<T>, minter: T::AccountId) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(Minters::<T, I>::contains_key(&minter), Error::<T, I>::NotMinter);
+
+            Minters::<T, I>::remove(&minter);
+
+            Self::deposit_event(Event::MinterRemoved { minter });
+
+            Ok(())
+        }
+
+        /// Change a minter's quota; the amount already minted this period still counts
+        #[pallet::weight(10_000)]
+        #[pallet::call_index(43)]
+        pub fn set_quota(origin: OriginFor<T>, minter: T::AccountId, quota: u128) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Minters::<T, I>::try_mutate(&minter, |maybe_minter| -> DispatchResult {
+                let info = maybe_minter.as_mut().ok_or(Error::<T, I>::NotMinter)?;
+                info.quota = quota;
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::MinterQuotaSet { minter, quota });
+
+            Ok(())
+        }
+    }
+
+    impl<T: Config<I>, I: 'static> Pallet<T, I> {
+        /// Get the account ID of the pallet
+        pub fn account_id() -> T::AccountId {
+            T::PalletId::get().into_account_truncating()
+        }
+
+        /// Count `amount` against a minter's quota, starting a new period once the current
+        /// one has lapsed
+        fn use_mint_quota(minter: &T::AccountId, amount: u128) -> DispatchResult {
+            Minters::<T, I>::try_mutate(minter, |maybe_minter| {
+                let info = maybe_minter.as_mut().ok_or(Error::<T, I>::NotMinter)?;
+
+                let now = frame_system::Pallet::<T>::block_number();
+                if now >= info.period_start.saturating_add(T::MintPeriod::get()) {
+                    info.period_start = now;
+                    info.minted = 0;
+                }
+
+                let minted = info.minted.saturating_add(amount);
+                ensure!(minted <= info.quota, Error::<T, I>::MintQuotaExceeded);
+                info.minted = minted;
+                Ok(())
+            })
+        }
+
+        /// Find the HTLC of `sender` locked under the SHA-256 or BLAKE2 hash of `preimage`
+        fn htlc_for_preimage(sender: &T::AccountId, preimage: &[u8]) -> Option<(H256, HtlcOf<T>)> {
+            [
+                (HashAlgorithm::Sha256, H256(sp_io::hashing::sha2_256(preimage))),
+                (HashAlgorithm::Blake2_256, H256(sp_io::hashing::blake2_256(preimage))),
+            ]
+            .into_iter()
+            .find_map(|(algorithm, hashlock)| {
+                Self::htlc(sender, hashlock)
+                    .filter(|htlc| htlc.algorithm == algorithm)
+                    .map(|htlc| (hashlock, htlc))
+            })
+        }
+
+        /// Amount the recipient of a stream can withdraw now
+        pub fn withdrawable_balance(stream_id: u32) -> u128 {
+            Self::stream(stream_id)
+                .map(|stream| stream.withdrawable_at(frame_system::Pallet::<T>::block_number()))
+                .unwrap_or_default()
+        }
+
+        /// Open escrows the account is party to
+        pub fn open_escrows(who: &T::AccountId) -> Vec<(u32, EscrowOf<T>)> {
+            EscrowsOf::<T, I>::iter_key_prefix(who)
+                .filter_map(|id| Self::escrow(id).map(|escrow| (id, escrow)))
+                .collect()
+        }
+
+        /// Unreserve escrowed funds back to the payer and close the escrow
+        fn refund_escrow(escrow_id: u32, escrow: EscrowOf<T>) {
+            T::Currency::unreserve_named(&ESCROW_ID, &escrow.payer, escrow.amount.saturated_into());
+            Self::close_escrow(escrow_id, &escrow);
+
+            Self::deposit_event(Event::EscrowRefunded {
+                escrow_id,
+                payer: escrow.payer,
+                amount: escrow.amount,
+            });
+        }
+
+        /// Remove an escrow and its index entries
+        fn close_escrow(escrow_id: u32, escrow: &EscrowOf<T>) {
+            Escrows::<T, I>::remove(escrow_id);
+            EscrowDeadlines::<T, I>::remove(escrow.deadline, escrow_id);
+            for party in [&escrow.payer, &escrow.payee, &escrow.arbiter] {
+                EscrowsOf::<T, I>::remove(party, escrow_id);
+            }
+            EscrowCount::<T, I>::mutate_exists(&escrow.payer, |maybe_count| {
+                *maybe_count = maybe_count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+            });
+        }
+
+        /// Refund escrows whose deadline has been reached, walking the deadline buckets from
+        /// the cursor for as long as `remaining_weight` allows
+        fn refund_expired_escrows(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            let per_bucket = T::DbWeight::get().reads_writes(1, 1);
+            let per_refund = T::DbWeight::get().reads_writes(4, 7);
+            let mut consumed = T::DbWeight::get().reads_writes(1, 1);
+            if !remaining_weight.all_gte(consumed) {
+                return Weight::zero();
+            }
+
+            // No escrow has been created yet
+            let mut cursor = match Self::escrow_refund_cursor() {
+                Some(cursor) => cursor,
+                None => return T::DbWeight::get().reads(1),
+            };
+            while cursor <= now {
+                if !remaining_weight.all_gte(consumed.saturating_add(per_bucket)) {
+                    break;
+                }
+                consumed = consumed.saturating_add(per_bucket);
+
+                // Refunding removes the id from the bucket, so the next read yields the next id
+                let mut bucket_done = true;
+                loop {
+                    if !remaining_weight.all_gte(consumed.saturating_add(per_refund)) {
+                        bucket_done = false;
+                        break;
+                    }
+                    let escrow_id = match EscrowDeadlines::<T, I>::iter_key_prefix(cursor).next() {
+                        Some(escrow_id) => escrow_id,
+                        None => break,
+                    };
+                    consumed = consumed.saturating_add(per_refund);
+                    match Self::escrow(escrow_id) {
+                        Some(escrow) => Self::refund_escrow(escrow_id, escrow),
+                        None => EscrowDeadlines::<T, I>::remove(cursor, escrow_id),
+                    }
+                }
+                if !bucket_done {
+                    break;
+                }
+                cursor = cursor.saturating_add(One::one());
+            }
+
+            EscrowRefundCursor::<T, I>::put(cursor);
+            consumed
+        }
+
+        /// Domain separator signed into permits, binding them to this chain and pallet instance
+        pub fn permit_domain_separator() -> [u8; 32] {
+            let genesis_hash = frame_system::Pallet::<T>::block_hash(T::BlockNumber::zero());
+            let pallet_index = <Self as PalletInfoAccess>::index() as u32;
+            sp_io::hashing::blake2_256(&(b"VedCoinPermit", genesis_hash, pallet_index).encode())
+        }
+
+        /// Get the treasury account receiving the non-burned share of fees
+        pub fn treasury_account() -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating(b"treasury")
+        }
+
+        /// Get the sub-account ID holding an allocation bucket
+        ///
+        /// The `b"bk"` prefix keeps bucket accounts apart from `account_id` and the other
+        /// sub-accounts once the id is truncated to the account length.
+        pub fn bucket_account(bucket: AllocationBucket) -> T::AccountId {
+            T::PalletId::get().into_sub_account_truncating((*b"bk", bucket))
+        }
+
+        /// Amount of tokens that can still be minted before reaching the maximum supply
+        ///
+        /// Everything ever minted is the currency's total issuance plus the tokens burned since,
+        /// so the headroom does not depend on the recorded `TokenInfo`.
+        pub fn mintable_supply() -> u128 {
+            let minted = T::Currency::total_issuance()
+                .saturated_into::<u128>()
+                .saturating_add(Self::burned_tokens());
+            T::MaxSupply::get().saturating_sub(minted)
+        }
+
+        /// Ensure that minting `amount` keeps the total supply within the maximum supply
+        pub fn ensure_can_mint(amount: u128) -> DispatchResult {
+            ensure!(amount <= Self::mintable_supply(), Error::<T, I>::MaxSupplyExceeded);
+            Ok(())
+        }
+
+        /// Record newly created tokens in the token info
+        pub(crate) fn note_minted(amount: u128) {
+            TokenInfoStorage::<T, I>::mutate(|maybe_info| {
+                if let Some(token_info) = maybe_info {
+                    token_info.circulating_supply =
+                        token_info.circulating_supply.saturating_add(amount);
+                    token_info.total_supply = token_info.total_supply.saturating_add(amount);
+                }
+            });
+        }
+
+        /// Mint new tokens into `to`, enforcing the maximum supply
+        ///
+        /// Returns the amount actually minted, which is zero when `amount` is below the existential
+        /// deposit of an account that does not exist yet.
+        pub fn do_mint(to: &T::AccountId, amount: u128) -> Result<u128, DispatchError> {
+            Self::ensure_can_mint(amount)?;
+            let minted: u128 =
+                T::Currency::deposit_creating(to, amount.saturated_into()).peek().saturated_into();
+            Self::note_minted(minted);
+            Ok(minted)
+        }
+
+        /// Blocks elapsed between `EmissionStart` and block `n`, if emission has started by then
+        fn emission_offset(n: T::BlockNumber) -> Option<u128> {
+            let start = Self::emission_start()?;
+            if n < start {
+                return None;
+            }
+            Some((n - start).saturated_into())
+        }
+
+        /// Zero-based emission year that block `n` belongs to
+        pub fn emission_year(n: T::BlockNumber) -> u128 {
+            let blocks_per_year: u128 = T::BlocksPerYear::get().saturated_into();
+            if blocks_per_year.is_zero() {
+                return 0;
+            }
+            Self::emission_offset(n).map_or(0, |offset| offset / blocks_per_year)
+        }
+
+        /// Total tokens scheduled for emission during the given zero-based year
+        pub fn annual_emission(year: u128) -> u128 {
+            if year.is_zero() {
+                return T::InitialEmission::get();
+            }
+
+            let halvings = (year - 1) / u128::from(T::HalvingInterval::get().max(1));
+            if halvings >= 128 {
+                0
+            } else {
+                T::AnnualEmission::get() >> halvings
+            }
+        }
+
+        /// Tokens scheduled for emission at block `n`
+        pub fn emission_at(n: T::BlockNumber) -> u128 {
+            let blocks_per_year: u128 = T::BlocksPerYear::get().saturated_into();
+            let offset = match Self::emission_offset(n) {
+                Some(offset) if !blocks_per_year.is_zero() => offset % blocks_per_year,
+                _ => return 0,
+            };
+            let annual = Self::annual_emission(Self::emission_year(n));
+
+            // Spread the yearly amount evenly, paying out rounding remainders as they accrue
+            annual.saturating_mul(offset + 1) / blocks_per_year
+                - annual.saturating_mul(offset) / blocks_per_year
+        }
+
+        /// Projected emission still to be minted after the current block
+        pub fn remaining_emission() -> u128 {
+            let now = frame_system::Pallet::<T>::block_number();
+            let blocks_per_year: u128 = T::BlocksPerYear::get().saturated_into();
+            if blocks_per_year.is_zero() {
+                return 0;
+            }
+
+            let mut year = Self::emission_year(now);
+            let mut remaining = match Self::emission_offset(now) {
+                Some(offset) => {
+                    let elapsed = offset % blocks_per_year + 1;
+                    let annual = Self::annual_emission(year);
+                    annual - annual.saturating_mul(elapsed) / blocks_per_year
+                }
+                None => Self::annual_emission(year),
+            };
+
+            loop {
+                year += 1;
+                let annual = Self::annual_emission(year);
+                if annual.is_zero() {
+                    break;
+                }
+                remaining = remaining.saturating_add(annual);
+            }
+
+            remaining.min(Self::mintable_supply())
+        }
+
+        /// Ensure neither `call` nor the whole pallet is paused
+        pub fn ensure_not_paused(call: PausableCall) -> DispatchResult {
+            Self::ensure_pallet_not_paused()?;
+            ensure!(!Self::is_paused(Some(call)), Error::<T, I>::CallPaused);
+            Ok(())
+        }
+
+        /// Ensure the whole pallet is not paused
+        ///
+        /// Every user-facing call checks this; governance, compliance and pause calls do not,
+        /// so the pallet can still be administered while paused.
+        pub fn ensure_pallet_not_paused() -> DispatchResult {
+            ensure!(!Self::is_paused(None), Error::<T, I>::CallPaused);
+            Ok(())
+        }
+
+        /// Whether the pause under `key` has not expired yet
+        fn is_paused(key: Option<PausableCall>) -> bool {
+            let now = frame_system::Pallet::<T>::block_number();
+            Self::paused_until(key).is_some_and(|until| now < until)
+        }
+
+        /// Pauses that have not expired yet
+        pub fn active_pauses() -> Vec<(Option<PausableCall>, T::BlockNumber)> {
+            let now = frame_system::Pallet::<T>::block_number();
+            Pauses::<T, I>::iter().filter(|(_, until)| now < *until).collect()
+        }
+
+        /// Ensure `who` is neither frozen nor blocklisted
+        pub fn ensure_can_send(who: &T::AccountId) -> DispatchResult {
+            ensure!(!FrozenAccounts::<T, I>::contains_key(who), Error::<T, I>::AccountFrozen);
+            Self::ensure_can_receive(who)
+        }
+
+        /// Ensure `who` is not blocklisted
+        pub fn ensure_can_receive(who: &T::AccountId) -> DispatchResult {
+            ensure!(!Blocklist::<T, I>::contains_key(who), Error::<T, I>::AccountBlocklisted);
+            Ok(())
+        }
+
+        /// Move tokens between accounts
+        pub fn do_transfer(from: &T::AccountId, to: &T::AccountId, amount: u128) -> DispatchResult {
+            Self::check_transfer(from, to, amount)?;
+            T::Currency::transfer(
+                from,
+                to,
+                amount.saturated_into(),
+                frame_support::traits::ExistenceRequirement::AllowDeath,
+            )?;
+            Self::settle_transfer(from, to, amount)
+        }
+
+        /// Transfer policy checked before `amount` moves from `from` to `to`: pauses,
+        /// compliance and the outflow limit
+        ///
+        /// Every path that pays one account from another runs this and `settle_transfer`
+        /// around the balance movement.
+        pub fn check_transfer(from: &T::AccountId, to: &T::AccountId, amount: u128) -> DispatchResult {
+            Self::ensure_not_paused(PausableCall::Transfer)?;
+            Self::ensure_can_send(from)?;
+            Self::ensure_can_receive(to)?;
+            Self::note_outflow(from, amount)
+        }
+
+        /// Transfer policy applied once `amount` has moved from `from` to `to`: the balance
+        /// share limit, the protocol fee and the HFT tax
+        pub fn settle_transfer(from: &T::AccountId, to: &T::AccountId, amount: u128) -> DispatchResult {
+            Self::ensure_within_balance_share(to)?;
+            Self::charge_transfer(from, amount)
+        }
+
+        /// Charge `from` the protocol fee and the HFT tax for a transfer of `amount`
+        fn charge_transfer(from: &T::AccountId, amount: u128) -> DispatchResult {
+            Self::charge_transfer_fee(from, amount)?;
+            Self::charge_hft_tax(from, amount)
+        }
+
+        /// Ensure a non-exempt account holds no more than `max_balance_share` of the
+        /// circulating supply
+        pub fn ensure_within_balance_share(who: &T::AccountId) -> DispatchResult {
+            let max_share = match Self::whale_limits().max_balance_share {
+                Some(share) if !WhaleExempt::<T, I>::contains_key(who) => share,
+                _ => return Ok(()),
+            };
+            let circulating = match Self::token_info() {
+                Some(info) => info.circulating_supply,
+                None => return Ok(()),
+            };
+
+            let balance: u128 = T::Currency::free_balance(who).saturated_into();
+            ensure!(
+                balance <= max_share.mul_floor(circulating),
+                Error::<T, I>::BalanceShareExceeded
+            );
+            Ok(())
+        }
+
+        /// Add `amount` to a non-exempt account's outflow over the last `OutflowWindow` blocks
+        fn note_outflow(who: &T::AccountId, amount: u128) -> DispatchResult {
+            let max_outflow = match Self::whale_limits().max_outflow {
+                Some(limit) if !WhaleExempt::<T, I>::contains_key(who) => limit,
+                _ => return Ok(()),
+            };
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let window_start = now.saturating_sub(T::OutflowWindow::get());
+            let mut outflow = Self::outflow(who);
+            outflow.retain(|(block, _)| *block > window_start);
+
+            let sent = outflow
+                .iter()
+                .fold(amount, |total, (_, sent)| total.saturating_add(*sent));
+            ensure!(sent <= max_outflow, Error::<T, I>::OutflowLimitExceeded);
+
+            match outflow.last_mut() {
+                Some((block, sent)) if *block == now => *sent = sent.saturating_add(amount),
+                _ => {
+                    // Merging the oldest bucket into the next one only keeps it counted for longer
+                    if outflow.is_full() && outflow.len() > 1 {
+                        let (_, oldest) = outflow.remove(0);
+                        outflow[0].1 = outflow[0].1.saturating_add(oldest);
+                    }
+                    if let Err((_, amount)) = outflow.try_push((now, amount)) {
+                        if let Some((block, sent)) = outflow.last_mut() {
+                            *block = now;
+                            *sent = sent.saturating_add(amount);
+                        }
+                    }
+                },
+            }
+            Outflows::<T, I>::insert(who, outflow);
+            Ok(())
+        }
+
+        /// Record a transfer in the sender's sliding window and burn the HFT tax once the
+        /// sender has already made `HftThreshold` transfers within `HftWindow` blocks
+        fn charge_hft_tax(who: &T::AccountId, amount: u128) -> DispatchResult {
+            if HftExempt::<T, I>::contains_key(who) || T::HftThreshold::get() == 0 {
+                return Ok(());
+            }
+
+            let now = frame_system::Pallet::<T>::block_number();
+            let window_start = now.saturating_sub(T::HftWindow::get());
+            let mut activity = Self::transfer_activity(who);
+            activity.retain(|block| *block > window_start);
+
+            let over_threshold = activity.is_full();
+            if over_threshold {
+                activity.remove(0);
+            }
+            let _ = activity.try_push(now);
+            TransferActivity::<T, I>::insert(who, activity);
+
+            let tax = T::HftTaxRate::get().mul_floor(amount);
+            if over_threshold && !tax.is_zero() {
+                Self::do_burn(who, tax, BurnReason::Fee)?;
+                Self::deposit_event(Event::HftTaxCollected { who: who.clone(), amount: tax });
+            }
+
+            Ok(())
+        }
+
+        /// Charge the protocol fee for a transfer of `amount`, burning `FeeBurnPercentage`
+        /// of it and sending the rest to the treasury
+        fn charge_transfer_fee(who: &T::AccountId, amount: u128) -> DispatchResult {
+            let fee = Self::transfer_fee().fee_for(amount);
+            if fee.is_zero() {
+                return Ok(());
+            }
+
+            let imbalance = T::Currency::withdraw(
+                who,
+                fee.saturated_into(),
+                WithdrawReasons::FEE,
+                frame_support::traits::ExistenceRequirement::AllowDeath,
+            )?;
+            let (burned, to_treasury) = Self::split_fee(imbalance);
+
+            Self::deposit_event(Event::FeeCharged {
+                who: who.clone(),
+                burned,
+                to_treasury,
+            });
+
+            Ok(())
+        }
+
+        /// Drop finished vesting schedules and lock what is still unvested
+        fn update_vesting_lock(who: &T::AccountId) {
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut schedules = Self::vesting(who);
+            schedules.retain(|schedule| !schedule.locked_at(now).is_zero());
+
+            let unvested = schedules
+                .iter()
+                .fold(0u128, |total, schedule| total.saturating_add(schedule.locked_at(now)));
+
+            if unvested.is_zero() {
+                T::Currency::remove_lock(VESTING_ID, who);
+                Vesting::<T, I>::remove(who);
+            } else {
+                T::Currency::set_lock(
+                    VESTING_ID,
+                    who,
+                    unvested.saturated_into(),
+                    WithdrawReasons::all(),
+                );
+                Vesting::<T, I>::insert(who, schedules);
+            }
+
+            Self::deposit_event(Event::VestingUpdated {
+                who: who.clone(),
+                unvested,
+            });
+        }
+
+        /// Locked and vested amounts across all vesting schedules of `who`
+        pub fn vesting_balance(who: &T::AccountId) -> (u128, u128) {
+            let now = frame_system::Pallet::<T>::block_number();
+            Self::vesting(who).iter().fold((0u128, 0u128), |(locked, vested), schedule| {
+                (
+                    locked.saturating_add(schedule.locked_at(now)),
+                    vested.saturating_add(schedule.vested_at(now)),
+                )
+            })
+        }
+
+        /// Burn tokens from `who` and record the burn
+        pub fn do_burn(who: &T::AccountId, amount: u128, reason: BurnReason) -> DispatchResult {
+            let imbalance = T::Currency::withdraw(
+                who,
+                amount.saturated_into(),
+                WithdrawReasons::all(),
+                frame_support::traits::ExistenceRequirement::AllowDeath,
+            )?;
+            // Dropping the imbalance reduces total issuance
+            drop(imbalance);
+            Self::note_burned(amount, reason);
+            Ok(())
+        }
+
+        /// Compare the recorded supply counters with balances and stakes
+        ///
+        /// Reads every `StakingInfoStorage` entry, so it is only meant for off-chain use. The
+        /// actual supply is the total issuance of `Config::Currency`.
+        pub fn supply_report() -> SupplyReport {
+            Self::report_from_stakes(StakingInfoStorage::<T, I>::iter_values())
+        }
+
+        /// Supply report with the actual stake summed over `stakes`
+        fn report_from_stakes(stakes: impl Iterator<Item = StakingInfo<T::AccountId, u128>>) -> SupplyReport {
+            let token_info = Self::token_info();
+            let issuance: u128 = T::Currency::total_issuance().saturated_into();
+            let staked = stakes.fold(0u128, |total, info| {
+                info.delegators
+                    .iter()
+                    .fold(total.saturating_add(info.staked), |total, (_, stake)| {
+                        total.saturating_add(*stake)
+                    })
+            });
+
+            SupplyReport {
+                recorded_circulating: token_info.as_ref().map_or(0, |info| info.circulating_supply),
+                actual_circulating: issuance,
+                recorded_total: token_info.as_ref().map_or(0, |info| info.total_supply),
+                actual_total: issuance.saturating_add(Self::burned_tokens()),
+                recorded_staked: Self::total_staked(),
+                actual_staked: staked,
+            }
+        }
+
+        /// Record burned tokens in the supply counters
+        pub fn note_burned(amount: u128, reason: BurnReason) {
+            TokenInfoStorage::<T, I>::mutate(|maybe_info| {
+                if let Some(token_info) = maybe_info {
+                    token_info.circulating_supply =
+                        token_info.circulating_supply.saturating_sub(amount);
+                }
+            });
+
+            BurnedTokens::<T, I>::put(Self::burned_tokens().saturating_add(amount));
+            BurnedByReason::<T, I>::mutate(reason, |burned| *burned = burned.saturating_add(amount));
+
+            Self::deposit_event(Event::TokensBurned { amount, reason });
+        }
+
+        /// Check an airdrop claim against its campaign and merkle root
+        pub fn validate_claim(
+            campaign_id: u32,
+            who: &T::AccountId,
+            amount: u128,
+            proof: &[H256],
+        ) -> Result<AirdropCampaign<T::BlockNumber>, Error<T, I>> {
+            let campaign = Self::airdrop_campaign(campaign_id).ok_or(Error::<T, I>::AirdropNotFound)?;
+
+            ensure!(
+                frame_system::Pallet::<T>::block_number() <= campaign.deadline,
+                Error::<T, I>::AirdropExpired
+            );
+            ensure!(
+                !AirdropClaims::<T, I>::contains_key(campaign_id, who),
+                Error::<T, I>::AlreadyClaimed
+            );
+            ensure!(
+                campaign.claimed.saturating_add(amount) <= campaign.cap,
+                Error::<T, I>::AirdropCapExceeded
+            );
+
+            let leaf = H256(sp_io::hashing::blake2_256(&(who, amount).encode()));
+            ensure!(
+                Self::merkle_root_from_proof(leaf, proof) == campaign.merkle_root,
+                Error::<T, I>::InvalidMerkleProof
+            );
+
+            Ok(campaign)
+        }
+
+        /// Fold a merkle proof into a root, hashing sibling pairs in sorted order
+        pub fn merkle_root_from_proof(leaf: H256, proof: &[H256]) -> H256 {
+            proof.iter().fold(leaf, |node, sibling| {
+                let (left, right) = if node <= *sibling { (node, *sibling) } else { (*sibling, node) };
+                let mut pair = [0u8; 64];
+                pair[..32].copy_from_slice(left.as_bytes());
+                pair[32..].copy_from_slice(right.as_bytes());
+                H256(sp_io::hashing::blake2_256(&pair))
+            })
+        }
+
+        /// Set the allowance of `spender` over `owner`'s tokens
+        fn set_allowance(owner: &T::AccountId, spender: &T::AccountId, amount: u128) {
+            if amount.is_zero() {
+                Allowances::<T, I>::remove(owner, spender);
+            } else {
+                Allowances::<T, I>::insert(owner, spender, amount);
+            }
+            Self::deposit_event(Event::Approval {
+                owner: owner.clone(),
+                spender: spender.clone(),
+                amount,
+            });
+        }
+
+        /// Reduce the allowance of `spender` over `owner`'s tokens by `amount`
+        pub fn spend_allowance(
+            owner: &T::AccountId,
+            spender: &T::AccountId,
+            amount: u128,
+        ) -> DispatchResult {
+            let remaining = Self::allowance(owner, spender)
+                .checked_sub(amount)
+                .ok_or(Error::<T, I>::InsufficientAllowance)?;
+            if remaining.is_zero() {
+                Allowances::<T, I>::remove(owner, spender);
+            } else {
+                Allowances::<T, I>::insert(owner, spender, remaining);
+            }
+            Ok(())
+        }
+
+        /// Amount `who` has staked, excluding any other reserved balance
+        pub fn staked_balance(who: &T::AccountId) -> u128 {
+            T::Currency::reserved_balance_named(&STAKING_ID, who).saturated_into()
+        }
+
+        /// Slash up to `amount` of the stake `staker` placed on `validator`, burning it
+        ///
+        /// Returns the amount actually slashed.
+        pub fn slash_stake(validator: &T::AccountId, staker: &T::AccountId, amount: u128) -> u128 {
+            let mut validator_info = match Self::staking_info(validator) {
+                Some(info) => info,
+                None => return 0,
+            };
+
+            let stake = if staker == validator {
+                &mut validator_info.staked
+            } else {
+                match validator_info.delegators.iter_mut().find(|(acc, _)| acc == staker) {
+                    Some((_, stake)) => stake,
+                    None => return 0,
+                }
+            };
+
+            let (imbalance, _) = T::Currency::slash_reserved_named(
+                &STAKING_ID,
+                staker,
+                amount.min(*stake).saturated_into(),
+            );
+            let slashed: u128 = imbalance.peek().saturated_into();
+            // Dropping the imbalance removes the slashed tokens from issuance
+            drop(imbalance);
+
+            *stake = stake.saturating_sub(slashed);
+            validator_info.delegators.retain(|(_, stake)| !stake.is_zero());
+            StakingInfoStorage::<T, I>::insert(validator, &validator_info);
+            TotalStaked::<T, I>::put(Self::total_staked().saturating_sub(slashed));
+            Self::note_burned(slashed, BurnReason::Slash);
+
+            Self::deposit_event(Event::StakeSlashed {
+                validator: validator.clone(),
+                staker: staker.clone(),
+                amount: slashed,
+            });
+
+            slashed
+        }
+
+        /// Calculate and distribute staking rewards
+        pub fn distribute_rewards(validator: &T::AccountId, total_rewards: u128) -> DispatchResult {
+            if let Some(validator_info) = Self::staking_info(validator) {
+                // Rewards are newly created tokens, so they count against the max supply
+                Self::ensure_can_mint(total_rewards)?;
+
+                let commission_amount = total_rewards
+                    .saturating_mul(validator_info.commission as u128)
+                    .saturating_div(100);
+                
+                let delegator_rewards = total_rewards.saturating_sub(commission_amount);
+                let total_delegated: u128 = validator_info.delegators
+                    .iter()
+                    .map(|(_, amount)| *amount)
+                    .sum();
+                let mut minted: u128 = 0;
+
+                // Distribute to delegators proportionally
+                for (delegator, stake) in &validator_info.delegators {
+                    if total_delegated > 0 {
+                        let delegator_reward = delegator_rewards
+                            .saturating_mul(*stake)
+                            .saturating_div(total_delegated);
+                        
+                        // Mint rewards to delegator
+                        let reward_amount = delegator_reward.saturated_into();
+                        let imbalance = T::Currency::deposit_creating(delegator, reward_amount);
+                        minted = minted.saturating_add(imbalance.peek().saturated_into());
+                    }
+                }
+
+                // Give commission to validator
+                let commission_balance = commission_amount.saturated_into();
+                let imbalance = T::Currency::deposit_creating(validator, commission_balance);
+                minted = minted.saturating_add(imbalance.peek().saturated_into());
+
+                Self::note_minted(minted);
+
+                Self::deposit_event(Event::RewardsDistributed {
+                    validator: validator.clone(),
+                    total_rewards,
+                });
+            }
+
+            Ok(())
+        }
+    }
+}
+
+// Runtime API for external queries
+sp_api::decl_runtime_apis! {
+    /// Version 2 added the methods marked `#[api_version(2)]`; block numbers in them are `u64`
+    #[api_version(2)]
+    pub trait VedCoinApi<AccountId>
+    where
+        AccountId: codec::Codec,
+    {
+        fn get_token_info() -> Option<TokenInfo>;
+        fn get_staking_info(account: AccountId) -> Option<StakingInfo<AccountId, u128>>;
+        fn get_total_staked() -> u128;
+        #[api_version(2)]
+        fn get_staked_balance(account: AccountId) -> u128;
+        fn get_burned_tokens() -> u128;
+        fn get_burned_by_reason(reason: BurnReason) -> u128;
+        /// Transaction fees burned so far in the current block
+        #[api_version(2)]
+        fn get_block_fee_burn() -> u128;
+        #[api_version(2)]
+        fn get_remaining_emission() -> u128;
+        #[api_version(2)]
+        fn allowance(owner: AccountId, spender: AccountId) -> u128;
+        #[api_version(2)]
+        fn get_recent_transfers(account: AccountId) -> Vec<MemoTransfer<AccountId, u64>>;
+        /// Returns `(locked, vested)` across all vesting schedules of the account
+        #[api_version(2)]
+        fn get_vesting_balance(account: AccountId) -> (u128, u128);
+        /// Active pauses with their expiry block; `None` is the whole pallet
+        #[api_version(2)]
+        fn get_pause_state() -> Vec<(Option<PausableCall>, u64)>;
+        /// Nonce the next permit of `owner` must carry
+        #[api_version(2)]
+        fn get_permit_nonce(owner: AccountId) -> u64;
+        /// Domain separator to include in signed permits
+        #[api_version(2)]
+        fn get_permit_domain_separator() -> [u8; 32];
+        /// Open escrows the account is payer, payee or arbiter of
+        #[api_version(2)]
+        fn get_open_escrows(account: AccountId) -> Vec<(u32, Escrow<AccountId, u64>)>;
+        /// Amount the recipient of the stream can withdraw at the current block
+        #[api_version(2)]
+        fn get_withdrawable_balance(stream_id: u32) -> u128;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{assert_ok, assert_noop, instances::{Instance1, Instance2}, parameter_types, traits::{GenesisBuild, OnFinalize, OnInitialize}, BoundedVec};
+    use sp_core::H256;
+    use frame_system as system;
+    use sp_runtime::{
+        testing::Header,
+        traits::{BlakeTwo256, IdentifyAccount, IdentityLookup, Lazy, ValidateUnsigned, Verify},
+        transaction_validity::{InvalidTransaction, TransactionSource},
+        Perbill,
+    };
+
+    type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    frame_support::construct_runtime!(
+        pub enum Test where
+            Block = Block,
+            NodeBlock = Block,
+            UncheckedExtrinsic = UncheckedExtrinsic,
+        {
+            System: frame_system,
+            Balances: pallet_balances,
+            VedGovBalances: pallet_balances::<Instance2>,
+            TransactionPayment: pallet_transaction_payment,
+            VedCoin: crate,
+            VedGov: crate::<Instance1>,
+        }
+    );
+
+    parameter_types! {
+        pub const BlockHashCount: u64 = 250;
+        pub BlockWeights: frame_system::limits::BlockWeights = {
+            let mut weights = frame_system::limits::BlockWeights::simple_max(
+                frame_support::weights::Weight::from_parts(1_000_000, u64::MAX),
+            );
+            weights.per_class.get_mut(frame_support::dispatch::DispatchClass::Normal).base_extrinsic =
+                frame_support::weights::Weight::from_parts(10, 0);
+            weights
+        };
+        pub const MaxSymbolLength: u32 = 8;
+        pub const ExistentialDeposit: u128 = 1;
+        pub const PalletId: frame_support::PalletId = frame_support::PalletId(*b"ved/coin");
+        pub const FeeBurnPercentage: Perbill = Perbill::from_percent(10);
+        pub const MaxSupply: u128 = 100_000_000_000 * 1_000_000_000_000_000_000;
+        pub const BlocksPerYear: u64 = 100;
+        pub const InitialEmission: u128 = 1_000_000;
+        pub const AnnualEmission: u128 = 200_000;
+        pub const HalvingInterval: u32 = 4;
+        pub const MaxBatchSize: u32 = 4;
+        pub const MaxMemoLength: u32 = 32;
+        pub const MaxRecentTransfers: u32 = 2;
+        pub const MaxVestingSchedules: u32 = 2;
+        pub const MinVestedTransfer: u128 = 100;
+        pub const MaxProofLength: u32 = 16;
+        pub const AirdropUnsignedPriority: u64 = 1 << 20;
+        pub const MaxReasonLength: u32 = 32;
+        pub const PauseDuration: u64 = 10;
+        pub const HftWindow: u64 = 10;
+        pub const HftThreshold: u32 = 8;
+        pub const HftTaxRate: Perbill = Perbill::from_percent(1);
+        pub const OutflowWindow: u64 = 10;
+        pub const MaxOutflowEntries: u32 = 4;
+        pub const MaxSponsoredCalls: u32 = 4;
+        pub const MaxEscrowsPerAccount: u32 = 2;
+        pub const MaxPreimageLength: u32 = 64;
+        pub const MintPeriod: u64 = 10;
+    }
+
+    impl system::Config for Test {
+        type BaseCallFilter = frame_support::traits::Everything;
+        type BlockWeights = BlockWeights;
+        type BlockLength = ();
+        type DbWeight = ();
+        type RuntimeOrigin = RuntimeOrigin;
+        type RuntimeCall = RuntimeCall;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = u128;
+        type Lookup = IdentityLookup<u128>;
+        type Header = Header;
+        type RuntimeEvent = RuntimeEvent;
+        type BlockHashCount = BlockHashCount;
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type AccountData = pallet_balances::AccountData<u128>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+        type SS58Prefix = ();
+        type OnSetCode = ();
+        type MaxConsumers = frame_support::traits::ConstU32<16>;
+    }
+
+    /// Signer of `PermitSignature`, identifying a `u128` mock account
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, Debug, TypeInfo)]
+    pub struct PermitSigner(u128);
+
+    impl IdentifyAccount for PermitSigner {
+        type AccountId = u128;
+        fn into_account(self) -> u128 {
+            self.0
+        }
+    }
+
+    /// `TestSignature` for `u128` accounts: valid when the signer and message match
+    #[derive(Clone, PartialEq, Eq, Encode, Decode, Debug, TypeInfo)]
+    pub struct PermitSignature(u128, Vec<u8>);
+
+    impl Verify for PermitSignature {
+        type Signer = PermitSigner;
+        fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &u128) -> bool {
+            self.0 == *signer && self.1 == msg.get()
+        }
+    }
+
+    impl Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type TransferOrigin = frame_system::EnsureSigned<u128>;
+        type BridgeOrigin = frame_system::EnsureSignedBy<Bridge, u128>;
+        type Currency = pallet_balances::Pallet<Test>;
+        type Fungible = pallet_balances::Pallet<Test>;
+        type MaxSymbolLength = MaxSymbolLength;
+        type PalletId = PalletId;
+        type FeeBurnPercentage = FeeBurnPercentage;
+        type MaxSupply = MaxSupply;
+        type BlocksPerYear = BlocksPerYear;
+        type InitialEmission = InitialEmission;
+        type AnnualEmission = AnnualEmission;
+        type HalvingInterval = HalvingInterval;
+        type MaxBatchSize = MaxBatchSize;
+        type MaxMemoLength = MaxMemoLength;
+        type MaxRecentTransfers = MaxRecentTransfers;
+        type MaxVestingSchedules = MaxVestingSchedules;
+        type MinVestedTransfer = MinVestedTransfer;
+        type MaxProofLength = MaxProofLength;
+        type AirdropUnsignedPriority = AirdropUnsignedPriority;
+        type ComplianceOrigin = frame_system::EnsureRoot<u128>;
+        type MaxReasonLength = MaxReasonLength;
+        type PauseOrigin = frame_system::EnsureRoot<u128>;
+        type PauseDuration = PauseDuration;
+        type HftWindow = HftWindow;
+        type HftThreshold = HftThreshold;
+        type HftTaxRate = HftTaxRate;
+        type OutflowWindow = OutflowWindow;
+        type MaxOutflowEntries = MaxOutflowEntries;
+        type OffchainSignature = PermitSignature;
+        type SigningPublicKey = PermitSigner;
+        type MaxSponsoredCalls = MaxSponsoredCalls;
+        type MaxEscrowsPerAccount = MaxEscrowsPerAccount;
+        type MaxPreimageLength = MaxPreimageLength;
+        type MintPeriod = MintPeriod;
+    }
+
+    parameter_types! {
+        pub const VedGovPalletId: frame_support::PalletId = frame_support::PalletId(*b"ved/govt");
+    }
+
+    frame_support::ord_parameter_types! {
+        pub const Government: u128 = 3;
+        pub const Bridge: u128 = 3;
+    }
+
+    impl Config<Instance1> for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type TransferOrigin = frame_system::EnsureSignedBy<Government, u128>;
+        type BridgeOrigin = frame_system::EnsureSignedBy<Bridge, u128>;
+        type Currency = pallet_balances::Pallet<Test, Instance2>;
+        type Fungible = pallet_balances::Pallet<Test, Instance2>;
+        type MaxSymbolLength = MaxSymbolLength;
+        type PalletId = VedGovPalletId;
+        type FeeBurnPercentage = FeeBurnPercentage;
+        type MaxSupply = MaxSupply;
+        type BlocksPerYear = BlocksPerYear;
+        type InitialEmission = InitialEmission;
+        type AnnualEmission = AnnualEmission;
+        type HalvingInterval = HalvingInterval;
+        type MaxBatchSize = MaxBatchSize;
+        type MaxMemoLength = MaxMemoLength;
+        type MaxRecentTransfers = MaxRecentTransfers;
+        type MaxVestingSchedules = MaxVestingSchedules;
+        type MinVestedTransfer = MinVestedTransfer;
+        type MaxProofLength = MaxProofLength;
+        type AirdropUnsignedPriority = AirdropUnsignedPriority;
+        type ComplianceOrigin = frame_system::EnsureRoot<u128>;
+        type MaxReasonLength = MaxReasonLength;
+        type PauseOrigin = frame_system::EnsureRoot<u128>;
+        type PauseDuration = PauseDuration;
+        type HftWindow = HftWindow;
+        type HftThreshold = HftThreshold;
+        type HftTaxRate = HftTaxRate;
+        type OutflowWindow = OutflowWindow;
+        type MaxOutflowEntries = MaxOutflowEntries;
+        type OffchainSignature = PermitSignature;
+        type SigningPublicKey = PermitSigner;
+        type MaxSponsoredCalls = MaxSponsoredCalls;
+        type MaxEscrowsPerAccount = MaxEscrowsPerAccount;
+        type MaxPreimageLength = MaxPreimageLength;
+        type MintPeriod = MintPeriod;
+    }
+
+    parameter_types! {
+        pub const ExistentialDeposit2: u128 = 1;
+    }
+    impl pallet_balances::Config for Test {
+        type Balance = u128;
+        type DustRemoval = ();
+        type RuntimeEvent = RuntimeEvent;
+        type ExistentialDeposit = ExistentialDeposit2;
+        type AccountStore = System;
+        type WeightInfo = ();
+        type MaxLocks = (); type MaxReserves = frame_support::traits::ConstU32<10>; type ReserveIdentifier = [u8; 8];
+        type FreezeIdentifier = (); type MaxFreezes = (); type MaxHolds = (); type HoldIdentifier = (); 
+    }
+
+    impl pallet_balances::Config<Instance2> for Test {
+        type Balance = u128;
+        type DustRemoval = ();
+        type RuntimeEvent = RuntimeEvent;
+        type ExistentialDeposit = ExistentialDeposit2;
+        type AccountStore = frame_support::traits::StorageMapShim<
+            pallet_balances::Account<Test, Instance2>,
+            u128,
+            pallet_balances::AccountData<u128>,
+        >;
+        type WeightInfo = ();
+        type MaxLocks = (); type MaxReserves = frame_support::traits::ConstU32<10>; type ReserveIdentifier = [u8; 8];
+        type FreezeIdentifier = (); type MaxFreezes = (); type MaxHolds = (); type HoldIdentifier = ();
+    }
+
+    impl pallet_transaction_payment::Config for Test {
+        type RuntimeEvent = RuntimeEvent;
+        type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, DealWithFees<Test>>;
+        type OperationalFeeMultiplier = frame_support::traits::ConstU8<5>;
+        type WeightToFee = frame_support::weights::IdentityFee<u128>;
+        type LengthToFee = frame_support::weights::IdentityFee<u128>;
+        type FeeMultiplierUpdate = ();
+    }
+
+    pub fn new_test_ext() -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+        pallet_balances::GenesisConfig::<Test> {
+            balances: vec![(1, 1_000_000), (2, 1_000_000), (3, 1_000_000)],
+        }.assimilate_storage(&mut t).unwrap();
+        pallet_balances::GenesisConfig::<Test, Instance2> {
+            balances: vec![(1, 1_000_000), (2, 1_000_000), (3, 1_000_000)],
+        }.assimilate_storage(&mut t).unwrap();
+        t.into()
+    }
+
+    /// Mint into the underlying currency until only `headroom` is left below the maximum supply
+    fn leave_headroom(headroom: u128) {
+        let total_issuance: u128 = Balances::total_issuance();
+        let _ = Balances::deposit_creating(&9, MaxSupply::get() - total_issuance - VedCoin::burned_tokens() - headroom);
+    }
+
+    fn run_to_block(n: u64) {
+        while System::block_number() < n {
+            VedCoin::on_finalize(System::block_number());
+            System::set_block_number(System::block_number() + 1);
+            VedCoin::on_initialize(System::block_number());
+        }
+    }
+
+    #[test]
+    fn genesis_seeds_token_info_and_allocations() {
+        let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+        GenesisBuild::<Test>::assimilate_storage(&crate::GenesisConfig {
+            symbol: b"VED".to_vec(),
+            name: b"VedCoin".to_vec(),
+            decimals: 18,
+            initial_supply: 1_000_000,
+            allocations: AllocationBucket::default_allocations(),
+        }, &mut t).unwrap();
+
+        sp_io::TestExternalities::from(t).execute_with(|| {
+            let info = VedCoin::token_info().unwrap();
+            assert_eq!(info.symbol, b"VED".to_vec());
+            assert_eq!(info.total_supply, 1_000_000);
+            assert_eq!(info.circulating_supply, 1_000_000);
+            assert_eq!(VedCoin::emission_start(), Some(1));
+            assert_ne!(VedCoin::bucket_account(AllocationBucket::Airdrop), VedCoin::account_id());
+            assert_eq!(Balances::free_balance(VedCoin::bucket_account(AllocationBucket::Airdrop)), 300_000);
+            assert_eq!(Balances::free_balance(VedCoin::bucket_account(AllocationBucket::GovernmentConversion)), 250_000);
+            assert_eq!(Balances::free_balance(VedCoin::bucket_account(AllocationBucket::Security)), 50_000);
+            assert_eq!(Balances::total_issuance(), 1_000_000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Allocation percentages must sum to 100%")]
+    fn genesis_rejects_allocations_not_summing_to_100_percent() {
+        let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+        GenesisBuild::<Test>::assimilate_storage(&crate::GenesisConfig {
+            initial_supply: 1_000_000,
+            allocations: vec![(AllocationBucket::Airdrop, Perbill::from_percent(60))],
+            ..Default::default()
+        }, &mut t).unwrap();
+    }
+
+    #[test]
+    fn initialize_token_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(
+                RuntimeOrigin::root(),
+                b"VED".to_vec(),
+                b"VedCoin".to_vec(),
+                18
+            ));
+            let info = VedCoin::token_info().unwrap();
+            assert_eq!(info.symbol, b"VED".to_vec());
+            assert_eq!(info.name, b"VedCoin".to_vec());
+            assert_eq!(info.decimals, 18);
+            assert_eq!((info.total_supply, info.circulating_supply), (3_000_000, 3_000_000));
+            assert_eq!(info.max_supply, MaxSupply::get());
+        });
+    }
+
+    #[test]
+    fn initialize_token_above_max_supply_fails() {
+        new_test_ext().execute_with(|| {
+            let _ = Balances::deposit_creating(&9, MaxSupply::get());
+            assert_noop!(
+                VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18),
+                Error::<Test>::MaxSupplyExceeded
+            );
+        });
+    }
+
+    #[test]
+    fn migrate_to_v2_adds_max_supply() {
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+        new_test_ext().execute_with(|| {
+            StorageVersion::new(1).put::<VedCoin>();
+            let old = (b"VED".to_vec(), b"VedCoin".to_vec(), 18u8, 5_000u128, 4_000u128);
+            frame_support::storage::unhashed::put_raw(&TokenInfoStorage::<Test>::hashed_key(), &old.encode());
+            assert!(VedCoin::token_info().is_none());
+
+            crate::migrations::v2::MigrateToV2::<Test>::on_runtime_upgrade();
+            assert_eq!(VedCoin::on_chain_storage_version(), 2);
+            let info = VedCoin::token_info().unwrap();
+            assert_eq!((info.total_supply, info.circulating_supply, info.max_supply), (5_000, 4_000, MaxSupply::get()));
+        });
+    }
+
+    #[test]
+    fn transfer_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100_000));
+        });
+    }
+
+    #[test]
+    fn transfer_charges_protocol_fee() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_noop!(VedCoin::set_transfer_fee(RuntimeOrigin::signed(1), 100, Perbill::from_percent(1)), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedCoin::set_transfer_fee(RuntimeOrigin::root(), 100, Perbill::from_percent(1)));
+
+            // Fee is 100 + 1% of 10_000 = 200; 10% of it is burned
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 10_000));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+            assert_eq!(Balances::free_balance(2), 1_010_000);
+            assert_eq!(Balances::free_balance(VedCoin::treasury_account()), 180);
+            assert_eq!(VedCoin::burned_tokens(), 20);
+            assert_eq!(VedCoin::token_info().unwrap().circulating_supply, 3_000_000 - 20);
+            System::assert_has_event(RuntimeEvent::VedCoin(crate::Event::FeeCharged { who: 1, burned: 20, to_treasury: 180 }));
+        });
+    }
+
+    #[test]
+    fn hft_tax_applies_above_threshold() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            for _ in 0..HftThreshold::get() {
+                assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1_000));
+            }
+            assert_eq!(VedCoin::burned_tokens(), 0);
+
+            // The next transfer in the window is taxed at 1%
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1_000));
+            assert_eq!(VedCoin::burned_tokens(), 10);
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 9_000 - 10);
+            System::assert_has_event(RuntimeEvent::VedCoin(crate::Event::HftTaxCollected { who: 1, amount: 10 }));
+
+            // Old transfers drop out of the window
+            System::set_block_number(11);
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1_000));
+            assert_eq!(VedCoin::burned_tokens(), 10);
+        });
+    }
+
+    #[test]
+    fn hft_exempt_accounts_are_not_taxed() {
+        new_test_ext().execute_with(|| {
+            assert_noop!(VedCoin::set_hft_exempt(RuntimeOrigin::signed(1), 1, true), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedCoin::set_hft_exempt(RuntimeOrigin::root(), 1, true));
+            for _ in 0..HftThreshold::get() + 2 {
+                assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1_000));
+            }
+            assert_eq!(VedCoin::burned_tokens(), 0);
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_000);
+        });
+    }
+
+    #[test]
+    fn balance_share_limit_applies_to_transfers_and_unstake() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 200_000));
+
+            // At most 30% of 3_000_000 per account
+            assert_ok!(VedCoin::set_whale_limits(RuntimeOrigin::root(), Some(Perbill::from_percent(30)), None));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100_000));
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1), Error::<Test>::BalanceShareExceeded);
+            assert_noop!(VedCoin::unstake(RuntimeOrigin::signed(2), 1, 1), Error::<Test>::BalanceShareExceeded);
+
+            assert_ok!(VedCoin::set_whale_exempt(RuntimeOrigin::root(), 2, true));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1));
+            assert_ok!(VedCoin::unstake(RuntimeOrigin::signed(2), 1, 200_000));
+        });
+    }
+
+    #[test]
+    fn outflow_limit_slides_with_window() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::set_whale_limits(RuntimeOrigin::root(), None, Some(5_000)));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 3_000));
+            assert_ok!(VedCoin::approve(RuntimeOrigin::signed(1), 3, 10_000));
+            System::set_block_number(5);
+            assert_noop!(VedCoin::transfer_from(RuntimeOrigin::signed(3), 1, 2, 2_001), Error::<Test>::OutflowLimitExceeded);
+            assert_ok!(VedCoin::transfer_from(RuntimeOrigin::signed(3), 1, 2, 2_000));
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1), Error::<Test>::OutflowLimitExceeded);
+
+            // Only the block 1 outflow has left the window, so a full limit cannot be sent
+            System::set_block_number(11);
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 3_001), Error::<Test>::OutflowLimitExceeded);
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 3_000));
+
+            System::set_block_number(15);
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 2_000));
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1), Error::<Test>::OutflowLimitExceeded);
+        });
+    }
+
+    #[test]
+    fn outflow_buckets_merge_when_full() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::set_whale_limits(RuntimeOrigin::root(), None, Some(5_000)));
+            for block in 1..=5 {
+                System::set_block_number(block);
+                assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1_000));
+            }
+            assert_eq!(VedCoin::outflow(1).into_inner(), vec![(2, 2_000), (3, 1_000), (4, 1_000), (5, 1_000)]);
+
+            // The block 1 outflow now expires with block 2
+            System::set_block_number(11);
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 1), Error::<Test>::OutflowLimitExceeded);
+            System::set_block_number(12);
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 2_000));
+        });
+    }
+
+    #[test]
+    fn permit_sets_allowance_from_signature() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let permit = Permit { owner: 1, spender: 3, amount: 5_000, nonce: 0, deadline: 10 };
+            let message = (VedCoin::permit_domain_separator(), &permit).encode();
+
+            assert_noop!(
+                VedCoin::permit(RuntimeOrigin::signed(2), permit.clone(), PermitSignature(2, message.clone())),
+                Error::<Test>::InvalidSignature
+            );
+            assert_ok!(VedCoin::permit(RuntimeOrigin::signed(2), permit.clone(), PermitSignature(1, message.clone())));
+            assert_eq!(VedCoin::allowance(1, 3), 5_000);
+            assert_eq!(VedCoin::permit_nonce(1), 1);
+
+            // Replays are rejected by the nonce
+            assert_noop!(
+                VedCoin::permit(RuntimeOrigin::signed(2), permit, PermitSignature(1, message)),
+                Error::<Test>::InvalidPermitNonce
+            );
+            assert_ok!(VedCoin::transfer_from(RuntimeOrigin::signed(3), 1, 2, 5_000));
+        });
+    }
+
+    #[test]
+    fn permit_rejects_expired_deadline() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(11);
+            let permit = Permit { owner: 1, spender: 3, amount: 5_000, nonce: 0, deadline: 10 };
+            let message = (VedCoin::permit_domain_separator(), &permit).encode();
+            assert_noop!(
+                VedCoin::permit(RuntimeOrigin::signed(2), permit, PermitSignature(1, message)),
+                Error::<Test>::PermitExpired
+            );
+        });
+    }
+
+    #[test]
+    fn sponsor_pays_fees_until_quota_is_exhausted() {
+        use frame_support::{dispatch::DispatchInfo, weights::Weight};
+        use pallet_transaction_payment::ChargeTransactionPayment;
+        use sp_runtime::traits::SignedExtension;
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::sponsor_deposit(RuntimeOrigin::signed(1), 1_000));
+            assert_ok!(VedCoin::set_sponsorship(RuntimeOrigin::signed(1), 4, 250));
+
+            let call = RuntimeCall::VedCoin(crate::Call::burn_tokens { amount: 1 });
+            let info = DispatchInfo { weight: Weight::from_parts(50, 0), ..Default::default() };
+            let sponsored = || ChargeSponsored::<Test, ()>::new(Some(1), ChargeTransactionPayment::from(0));
+
+            // Base extrinsic weight 10, length 40 and weight 50 add up to a fee of 100
+            assert_ok!(sponsored().pre_dispatch(&4, &call, &info, 40));
+            assert_eq!(Balances::reserved_balance(1), 900);
+            assert_eq!(VedCoin::sponsorship(1, 4), Some(150));
+            // 10% of the fee is burned, the rest goes to the treasury
+            assert_eq!(VedCoin::burned_tokens(), 10);
+            assert_eq!(Balances::free_balance(VedCoin::treasury_account()), 90);
+
+            assert_ok!(sponsored().pre_dispatch(&4, &call, &info, 40));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::SponsorshipExhausted { sponsor: 1, beneficiary: 4 }));
+            assert_eq!(
+                sponsored().pre_dispatch(&4, &call, &info, 40),
+                Err(InvalidTransaction::Payment.into())
+            );
+            // Unsponsored beneficiaries are rejected
+            assert_eq!(sponsored().validate(&5, &call, &info, 40), Err(InvalidTransaction::Payment.into()));
+        });
+    }
+
+    #[test]
+    fn sponsorship_can_be_restricted_to_call_types() {
+        use frame_support::dispatch::DispatchInfo;
+        use pallet_transaction_payment::ChargeTransactionPayment;
+        use sp_runtime::traits::SignedExtension;
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::sponsor_deposit(RuntimeOrigin::signed(1), 1_000));
+            assert_ok!(VedCoin::set_sponsorship(RuntimeOrigin::signed(1), 4, 1_000));
+            let burn = RuntimeCall::VedCoin(crate::Call::burn_tokens { amount: 1 });
+            let transfer = RuntimeCall::VedCoin(crate::Call::transfer { to: 2, amount: 1 });
+            let burn_id = (burn.encode()[0], burn.encode()[1]);
+            assert_ok!(VedCoin::set_sponsored_calls(RuntimeOrigin::signed(1), BoundedVec::truncate_from(vec![burn_id])));
+
+            let sponsored = ChargeSponsored::<Test, ()>::new(Some(1), ChargeTransactionPayment::from(0));
+            let info = DispatchInfo::default();
+            assert_ok!(sponsored.validate(&4, &burn, &info, 0));
+            assert_eq!(sponsored.validate(&4, &transfer, &info, 0), Err(InvalidTransaction::Call.into()));
+
+            // Without a sponsor `ChargeTransactionPayment` charges the signer the base fee
+            let unsponsored = ChargeSponsored::<Test, ()>::new(None, ChargeTransactionPayment::from(0));
+            assert_ok!(unsponsored.pre_dispatch(&1, &transfer, &info, 0));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 1_000 - 10);
+        });
+    }
+
+    #[test]
+    fn escrow_release_and_refund() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 5_000, 3, 10));
+            assert_eq!(Balances::reserved_balance(1), 5_000);
+            assert_eq!(VedCoin::open_escrows(&2).len(), 1);
+
+            assert_noop!(VedCoin::release(RuntimeOrigin::signed(2), 0), Error::<Test>::NotEscrowParty);
+            assert_ok!(VedCoin::release(RuntimeOrigin::signed(3), 0));
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert_eq!(Balances::free_balance(2), 1_005_000);
+            assert!(VedCoin::open_escrows(&1).is_empty());
+            assert_noop!(VedCoin::refund(RuntimeOrigin::signed(2), 0), Error::<Test>::EscrowNotFound);
+
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 5_000, 3, 10));
+            assert_noop!(VedCoin::refund(RuntimeOrigin::signed(1), 1), Error::<Test>::NotEscrowParty);
+            assert_ok!(VedCoin::refund(RuntimeOrigin::signed(2), 1));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 5_000);
+            assert_eq!(Balances::reserved_balance(1), 0);
+        });
+    }
+
+    #[test]
+    fn escrow_is_refunded_after_deadline_in_on_idle() {
+        use frame_support::{traits::OnIdle, weights::Weight};
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 5_000, 3, 5));
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 1_000, 3, 20));
+
+            VedCoin::on_idle(4, Weight::MAX);
+            assert_eq!(Balances::reserved_balance(1), 6_000);
+
+            VedCoin::on_idle(5, Weight::MAX);
+            assert_eq!(Balances::reserved_balance(1), 1_000);
+            assert!(VedCoin::escrow(0).is_none());
+            System::assert_has_event(RuntimeEvent::VedCoin(crate::Event::EscrowRefunded { escrow_id: 0, payer: 1, amount: 5_000 }));
+            assert_eq!(VedCoin::open_escrows(&1), vec![(1, VedCoin::escrow(1).unwrap())]);
+            assert_eq!(VedCoin::escrow_refund_cursor(), Some(6));
+        });
+    }
+
+    #[test]
+    fn escrow_refund_cursor_starts_at_first_escrow() {
+        use frame_support::{traits::OnIdle, weights::Weight};
+        new_test_ext().execute_with(|| {
+            System::set_block_number(100);
+            VedCoin::on_idle(100, Weight::MAX);
+            assert_eq!(VedCoin::escrow_refund_cursor(), None);
+
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 5_000, 3, 105));
+            assert_eq!(VedCoin::escrow_refund_cursor(), Some(100));
+            VedCoin::on_idle(105, Weight::MAX);
+            assert!(VedCoin::escrow(0).is_none());
+            assert_eq!(VedCoin::escrow_refund_cursor(), Some(106));
+        });
+    }
+
+    #[test]
+    fn escrow_cap_counts_only_escrows_paid_into() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 3, 1_000, 3, 10));
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 3, 1_000, 3, 10));
+            assert_noop!(
+                VedCoin::create_escrow(RuntimeOrigin::signed(1), 3, 1_000, 3, 10),
+                Error::<Test>::TooManyEscrows
+            );
+            assert_eq!(VedCoin::escrow_count(1), 2);
+
+            // Being named as payee or arbiter does not use up an account's own escrows
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(2), 3, 1_000, 3, 10));
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(3), 1, 1_000, 2, 10));
+            assert_eq!(VedCoin::open_escrows(&3).len(), 4);
+
+            assert_ok!(VedCoin::refund(RuntimeOrigin::signed(3), 0));
+            assert_eq!(VedCoin::escrow_count(1), 1);
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 3, 1_000, 3, 10));
+        });
+    }
+
+    #[test]
+    fn escrow_applies_transfer_policy_on_create() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::set_transfer_fee(RuntimeOrigin::root(), 100, Perbill::from_percent(1)));
+
+            assert_ok!(VedCoin::set_whale_limits(RuntimeOrigin::root(), None, Some(5_000)));
+            assert_noop!(
+                VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 10_000, 3, 10),
+                Error::<Test>::OutflowLimitExceeded
+            );
+            assert_ok!(VedCoin::set_whale_limits(RuntimeOrigin::root(), None, None));
+
+            assert_ok!(VedCoin::create_escrow(RuntimeOrigin::signed(1), 2, 10_000, 3, 10));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+            System::assert_has_event(RuntimeEvent::VedCoin(crate::Event::FeeCharged { who: 1, burned: 20, to_treasury: 180 }));
+
+            assert_ok!(VedCoin::pause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+            assert_noop!(VedCoin::release(RuntimeOrigin::signed(3), 0), Error::<Test>::CallPaused);
+            assert_ok!(VedCoin::unpause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+
+            // Release does not depend on the payer's state
+            assert_ok!(VedCoin::freeze_account(RuntimeOrigin::root(), 1, BoundedVec::default()));
+            assert_ok!(VedCoin::release(RuntimeOrigin::signed(3), 0));
+            assert_eq!(Balances::free_balance(2), 1_010_000);
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+        });
+    }
+
+    #[test]
+    fn stream_accrues_and_can_be_withdrawn() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_noop!(VedCoin::create_stream(RuntimeOrigin::signed(1), 2, 100, 5, 5), Error::<Test>::InvalidStream);
+            assert_ok!(VedCoin::create_stream(RuntimeOrigin::signed(1), 2, 100, 5, 15));
+            assert_eq!(Balances::reserved_balance(1), 1_000);
+            assert_noop!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(2), 0), Error::<Test>::NothingToWithdraw);
+
+            System::set_block_number(8);
+            assert_eq!(VedCoin::withdrawable_balance(0), 300);
+            assert_noop!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(1), 0), Error::<Test>::NotStreamParty);
+            assert_ok!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(2), 0));
+            assert_eq!(Balances::free_balance(2), 1_000_300);
+            assert_eq!(VedCoin::withdrawable_balance(0), 0);
+
+            // Accrual stops at `stop`; a fully paid stream is removed
+            System::set_block_number(30);
+            assert_eq!(VedCoin::withdrawable_balance(0), 700);
+            assert_ok!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(2), 0));
+            assert_eq!(Balances::free_balance(2), 1_001_000);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert!(VedCoin::stream(0).is_none());
+        });
+    }
+
+    #[test]
+    fn cancel_stream_refunds_unaccrued_remainder() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::create_stream(RuntimeOrigin::signed(1), 2, 100, 5, 15));
+            System::set_block_number(8);
+            assert_ok!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(2), 0));
+
+            System::set_block_number(10);
+            assert_noop!(VedCoin::cancel_stream(RuntimeOrigin::signed(3), 0), Error::<Test>::NotStreamParty);
+            assert_ok!(VedCoin::cancel_stream(RuntimeOrigin::signed(1), 0));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::StreamCancelled {
+                stream_id: 0,
+                paid_to_recipient: 200,
+                refunded_to_sender: 500,
+            }));
+            assert_eq!(Balances::free_balance(2), 1_000_500);
+            assert_eq!(Balances::free_balance(1), 999_500);
+            assert_eq!(Balances::reserved_balance(1), 0);
+        });
+    }
+
+    #[test]
+    fn stream_applies_transfer_policy() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::set_transfer_fee(RuntimeOrigin::root(), 100, Perbill::from_percent(1)));
+
+            // A one-block stream is charged like a transfer of its deposit
+            assert_ok!(VedCoin::create_stream(RuntimeOrigin::signed(1), 2, 10_000, 1, 2));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+            System::assert_has_event(RuntimeEvent::VedCoin(crate::Event::FeeCharged { who: 1, burned: 20, to_treasury: 180 }));
+
+            assert_ok!(VedCoin::set_whale_limits(RuntimeOrigin::root(), None, Some(5_000)));
+            assert_noop!(
+                VedCoin::create_stream(RuntimeOrigin::signed(1), 2, 100, 1, 52),
+                Error::<Test>::OutflowLimitExceeded
+            );
+
+            System::set_block_number(2);
+            assert_ok!(VedCoin::pause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+            assert_noop!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(2), 0), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::cancel_stream(RuntimeOrigin::signed(1), 0), Error::<Test>::CallPaused);
+            assert_ok!(VedCoin::unpause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+
+            assert_ok!(VedCoin::cancel_stream(RuntimeOrigin::signed(1), 0));
+            assert_eq!(Balances::free_balance(2), 1_010_000);
+        });
+    }
+
+    #[test]
+    fn cancel_stream_leaves_accrued_part_for_blocked_recipient() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::create_stream(RuntimeOrigin::signed(1), 2, 100, 5, 15));
+            assert_ok!(VedCoin::add_to_blocklist(RuntimeOrigin::root(), 2, BoundedVec::default()));
+
+            // The unaccrued part is refunded and the accrued part stays claimable
+            System::set_block_number(8);
+            assert_ok!(VedCoin::cancel_stream(RuntimeOrigin::signed(1), 0));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::StreamCancelled {
+                stream_id: 0,
+                paid_to_recipient: 0,
+                refunded_to_sender: 700,
+            }));
+            assert_eq!(Balances::reserved_balance(1), 300);
+            assert_eq!(Balances::free_balance(2), 1_000_000);
+
+            System::set_block_number(20);
+            assert_eq!(VedCoin::withdrawable_balance(0), 300);
+            assert_ok!(VedCoin::remove_from_blocklist(RuntimeOrigin::root(), 2));
+            assert_ok!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(2), 0));
+            assert_eq!(Balances::free_balance(2), 1_000_300);
+            assert_eq!(Balances::reserved_balance(1), 0);
+            assert!(VedCoin::stream(0).is_none());
+        });
+    }
+
+    #[test]
+    fn htlc_claim_reveals_preimage() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let preimage = b"atomic swap secret".to_vec();
+            let sha_lock = H256(sp_io::hashing::sha2_256(&preimage));
+            let blake_lock = H256(sp_io::hashing::blake2_256(&preimage));
+            assert_ok!(VedCoin::htlc_lock(RuntimeOrigin::signed(1), 2, 5_000, sha_lock, HashAlgorithm::Sha256, 10));
+            assert_ok!(VedCoin::htlc_lock(RuntimeOrigin::signed(1), 3, 1_000, blake_lock, HashAlgorithm::Blake2_256, 10));
+            assert_noop!(
+                VedCoin::htlc_lock(RuntimeOrigin::signed(1), 2, 5_000, sha_lock, HashAlgorithm::Sha256, 10),
+                Error::<Test>::HtlcExists
+            );
+            assert_eq!(Balances::reserved_balance(1), 6_000);
+
+            let bounded = BoundedVec::truncate_from(preimage.clone());
+            assert_noop!(VedCoin::htlc_refund(RuntimeOrigin::signed(1), 1, sha_lock), Error::<Test>::HtlcNotExpired);
+            assert_noop!(
+                VedCoin::htlc_claim(RuntimeOrigin::signed(4), 1, BoundedVec::truncate_from(b"wrong".to_vec())),
+                Error::<Test>::HtlcNotFound
+            );
+            assert_noop!(VedCoin::htlc_claim(RuntimeOrigin::signed(4), 2, bounded.clone()), Error::<Test>::HtlcNotFound);
+
+            // The SHA-256 lock is found first; claiming again finds the BLAKE2 lock
+            assert_ok!(VedCoin::htlc_claim(RuntimeOrigin::signed(4), 1, bounded.clone()));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::HtlcClaimed {
+                hashlock: sha_lock,
+                sender: 1,
+                recipient: 2,
+                amount: 5_000,
+                preimage: preimage.clone(),
+            }));
+            assert_eq!(Balances::free_balance(2), 1_005_000);
+            assert_ok!(VedCoin::htlc_claim(RuntimeOrigin::signed(4), 1, bounded));
+            assert_eq!(Balances::free_balance(3), 1_001_000);
+            assert_eq!(Balances::reserved_balance(1), 0);
+        });
+    }
+
+    #[test]
+    fn htlc_hashlock_is_scoped_to_its_sender() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let preimage = b"atomic swap secret".to_vec();
+            let hashlock = H256(sp_io::hashing::sha2_256(&preimage));
+
+            // Locking the same hashlock first does not block another sender
+            assert_ok!(VedCoin::htlc_lock(RuntimeOrigin::signed(3), 3, 1, hashlock, HashAlgorithm::Sha256, 2));
+            assert_ok!(VedCoin::htlc_lock(RuntimeOrigin::signed(1), 2, 5_000, hashlock, HashAlgorithm::Sha256, 10));
+            assert_ok!(VedCoin::htlc_claim(RuntimeOrigin::signed(2), 1, BoundedVec::truncate_from(preimage)));
+            assert_eq!(Balances::free_balance(2), 1_005_000);
+            assert!(VedCoin::htlc(3, hashlock).is_some());
+        });
+    }
+
+    #[test]
+    fn htlc_applies_transfer_policy_on_lock() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::set_transfer_fee(RuntimeOrigin::root(), 100, Perbill::from_percent(1)));
+            let preimage = BoundedVec::truncate_from(b"atomic swap secret".to_vec());
+            let hashlock = H256(sp_io::hashing::sha2_256(&preimage));
+
+            assert_ok!(VedCoin::freeze_account(RuntimeOrigin::root(), 1, BoundedVec::default()));
+            assert_noop!(
+                VedCoin::htlc_lock(RuntimeOrigin::signed(1), 2, 10_000, hashlock, HashAlgorithm::Sha256, 10),
+                Error::<Test>::AccountFrozen
+            );
+            assert_ok!(VedCoin::unfreeze_account(RuntimeOrigin::root(), 1));
+
+            assert_ok!(VedCoin::htlc_lock(RuntimeOrigin::signed(1), 2, 10_000, hashlock, HashAlgorithm::Sha256, 10));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+            System::assert_has_event(RuntimeEvent::VedCoin(crate::Event::FeeCharged { who: 1, burned: 20, to_treasury: 180 }));
+
+            assert_ok!(VedCoin::pause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+            assert_noop!(VedCoin::htlc_claim(RuntimeOrigin::signed(2), 1, preimage.clone()), Error::<Test>::CallPaused);
+            assert_ok!(VedCoin::unpause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+
+            // Once the preimage is public the sender cannot make the claim fail
+            assert_ok!(VedCoin::freeze_account(RuntimeOrigin::root(), 1, BoundedVec::default()));
+            assert_ok!(VedCoin::htlc_claim(RuntimeOrigin::signed(2), 1, preimage));
+            assert_eq!(Balances::free_balance(2), 1_010_000);
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+        });
+    }
+
+    #[test]
+    fn htlc_refund_after_timelock() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let preimage = b"atomic swap secret".to_vec();
+            let hashlock = H256(sp_io::hashing::sha2_256(&preimage));
+            assert_ok!(VedCoin::htlc_lock(RuntimeOrigin::signed(1), 2, 5_000, hashlock, HashAlgorithm::Sha256, 10));
+
+            System::set_block_number(10);
+            assert_noop!(
+                VedCoin::htlc_claim(RuntimeOrigin::signed(2), 1, BoundedVec::truncate_from(preimage)),
+                Error::<Test>::HtlcExpired
+            );
+            assert_ok!(VedCoin::htlc_refund(RuntimeOrigin::signed(2), 1, hashlock));
+            assert_eq!(Balances::free_balance(1), 1_000_000);
+            assert!(VedCoin::htlc(1, hashlock).is_none());
+        });
+    }
+
+    #[test]
+    fn transfer_batch_works() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let batch = BoundedVec::try_from(vec![(2, 100_000), (3, 50_000)]).unwrap();
+            assert_ok!(VedCoin::transfer_batch(RuntimeOrigin::signed(1), batch));
+            assert_eq!(Balances::free_balance(1), 850_000);
+            assert_eq!(Balances::free_balance(2), 1_100_000);
+            assert_eq!(Balances::free_balance(3), 1_050_000);
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::BatchTransferred { from: 1, recipients: 2, total: 150_000 }));
+        });
+    }
+
+    #[test]
+    fn transfer_batch_is_all_or_nothing() {
+        new_test_ext().execute_with(|| {
+            let zero_amount = BoundedVec::try_from(vec![(2, 100_000), (3, 0)]).unwrap();
+            assert_noop!(VedCoin::transfer_batch(RuntimeOrigin::signed(1), zero_amount), Error::<Test>::ZeroStake);
+
+            let too_much = BoundedVec::try_from(vec![(2, 600_000), (3, 600_000)]).unwrap();
+            assert_noop!(VedCoin::transfer_batch(RuntimeOrigin::signed(1), too_much), Error::<Test>::InsufficientBalance);
+
+            assert_noop!(VedCoin::transfer_batch(RuntimeOrigin::signed(1), BoundedVec::default()), Error::<Test>::EmptyBatch);
+        });
+    }
+
+    #[test]
+    fn transfer_with_memo_works() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            let memo = BoundedVec::try_from(b"invoice 42".to_vec()).unwrap();
+            let reference = Some(PaymentReference::Invoice([42u8; 32]));
+            assert_ok!(VedCoin::transfer_with_memo(RuntimeOrigin::signed(1), 2, 1_000, memo, reference.clone()));
+            assert_eq!(Balances::free_balance(2), 1_001_000);
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::TokensTransferredWithMemo {
+                from: 1,
+                to: 2,
+                amount: 1_000,
+                memo: b"invoice 42".to_vec(),
+                reference: reference.clone(),
+            }));
+
+            let recent = VedCoin::recent_transfers(2);
+            assert_eq!(recent.len(), 1);
+            assert_eq!(recent[0].from, 1);
+            assert_eq!(recent[0].reference, reference);
+        });
+    }
+
+    #[test]
+    fn recent_transfers_keep_latest_entries() {
+        new_test_ext().execute_with(|| {
+            for amount in 1..=3u128 {
+                assert_ok!(VedCoin::transfer_with_memo(RuntimeOrigin::signed(1), 2, amount, BoundedVec::default(), None));
+            }
+            let amounts: Vec<u128> = VedCoin::recent_transfers(2).iter().map(|t| t.amount).collect();
+            assert_eq!(amounts, vec![2, 3]);
+        });
+    }
+
+    #[test]
+    fn vested_transfer_locks_and_releases_tokens() {
+        new_test_ext().execute_with(|| {
+            let schedule = VestingSchedule { locked: 100_000, start: 0, cliff: 10, duration: 100, step: 1 };
+            assert_ok!(VedCoin::vested_transfer(RuntimeOrigin::signed(1), 4, schedule));
+            assert_eq!(Balances::free_balance(4), 100_000);
+            assert_eq!(VedCoin::vesting_balance(&4), (100_000, 0));
+            assert_noop!(
+                VedCoin::transfer(RuntimeOrigin::signed(4), 2, 1),
+                sp_runtime::TokenError::Frozen
+            );
+
+            // Nothing is released before the cliff
+            System::set_block_number(9);
+            assert_eq!(VedCoin::vesting_balance(&4), (100_000, 0));
+
+            System::set_block_number(50);
+            assert_eq!(VedCoin::vesting_balance(&4), (50_000, 50_000));
+            assert_ok!(VedCoin::vest(RuntimeOrigin::signed(4)));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(4), 2, 50_000));
+
+            System::set_block_number(100);
+            assert_ok!(VedCoin::vest(RuntimeOrigin::signed(4)));
+            assert!(!Vesting::<Test>::contains_key(4));
+        });
+    }
+
+    #[test]
+    fn step_vesting_releases_whole_steps() {
+        let schedule = VestingSchedule { locked: 1_000u128, start: 10u64, cliff: 0, duration: 100, step: 25 };
+        assert_eq!(schedule.vested_at(34), 0);
+        assert_eq!(schedule.vested_at(35), 250);
+        assert_eq!(schedule.vested_at(84), 500);
+        assert_eq!(schedule.vested_at(110), 1_000);
+    }
+
+    #[test]
+    fn force_remove_vesting_requires_root() {
+        new_test_ext().execute_with(|| {
+            let schedule = VestingSchedule { locked: 100_000, start: 0, cliff: 0, duration: 100, step: 1 };
+            assert_ok!(VedCoin::vested_transfer(RuntimeOrigin::signed(1), 4, schedule));
+            assert_noop!(VedCoin::force_remove_vesting(RuntimeOrigin::signed(1), 4, 0), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedCoin::force_remove_vesting(RuntimeOrigin::root(), 4, 0));
+            assert_eq!(VedCoin::vesting_balance(&4), (0, 0));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(4), 2, 50_000));
+        });
+    }
+
+    fn airdrop_leaf(who: u128, amount: u128) -> H256 {
+        H256(sp_io::hashing::blake2_256(&(who, amount).encode()))
+    }
+
+    fn setup_airdrop() -> (H256, H256) {
+        let _ = Balances::deposit_creating(&VedCoin::bucket_account(AllocationBucket::Airdrop), 10_000);
+        let (leaf_4, leaf_5) = (airdrop_leaf(4, 1_000), airdrop_leaf(5, 2_000));
+        let root = VedCoin::merkle_root_from_proof(leaf_4, &[leaf_5]);
+        assert_ok!(VedCoin::register_airdrop(RuntimeOrigin::root(), root, 3_000, 10));
+        (leaf_4, leaf_5)
+    }
+
+    #[test]
+    fn claim_airdrop_works() {
+        new_test_ext().execute_with(|| {
+            let (_, leaf_5) = setup_airdrop();
+            let proof = BoundedVec::try_from(vec![leaf_5]).unwrap();
+            assert_ok!(VedCoin::claim_airdrop(RuntimeOrigin::none(), 0, 4, 1_000, proof.clone()));
+            assert_eq!(Balances::free_balance(4), 1_000);
+            assert_noop!(VedCoin::claim_airdrop(RuntimeOrigin::none(), 0, 4, 1_000, proof), Error::<Test>::AlreadyClaimed);
+
+            let bad_proof = BoundedVec::try_from(vec![leaf_5]).unwrap();
+            assert_noop!(VedCoin::claim_airdrop(RuntimeOrigin::none(), 0, 5, 2_000, bad_proof), Error::<Test>::InvalidMerkleProof);
+        });
+    }
+
+    #[test]
+    fn claim_airdrop_is_validated_unsigned() {
+        new_test_ext().execute_with(|| {
+            let (leaf_4, leaf_5) = setup_airdrop();
+            let valid = crate::Call::claim_airdrop {
+                campaign_id: 0,
+                who: 5,
+                amount: 2_000,
+                proof: BoundedVec::try_from(vec![leaf_4]).unwrap(),
+            };
+            assert!(<VedCoin as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &valid).is_ok());
+
+            let forged = crate::Call::claim_airdrop {
+                campaign_id: 0,
+                who: 5,
+                amount: 3_000,
+                proof: BoundedVec::try_from(vec![leaf_5]).unwrap(),
+            };
+            assert_eq!(
+                <VedCoin as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &forged),
+                InvalidTransaction::BadProof.into()
+            );
+        });
+    }
+
+    #[test]
+    fn expired_airdrop_burns_unclaimed_tokens() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            let (_, leaf_5) = setup_airdrop();
+            assert_ok!(VedCoin::claim_airdrop(RuntimeOrigin::none(), 0, 4, 1_000, BoundedVec::try_from(vec![leaf_5]).unwrap()));
+            assert_noop!(VedCoin::expire_airdrop(RuntimeOrigin::signed(1), 0), Error::<Test>::AirdropNotExpired);
+
+            System::set_block_number(11);
+            assert_ok!(VedCoin::expire_airdrop(RuntimeOrigin::signed(1), 0));
+            assert_eq!(VedCoin::burned_tokens(), 2_000);
+            assert_eq!(VedCoin::token_info().unwrap().circulating_supply, 3_000_000 - 2_000);
+            assert_eq!(Balances::free_balance(VedCoin::bucket_account(AllocationBucket::Airdrop)), 7_000);
+            assert!(VedCoin::airdrop_campaign(0).is_none());
+        });
+    }
+
+    #[test]
+    fn approve_and_transfer_from_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::approve(RuntimeOrigin::signed(1), 2, 100_000));
+            assert_eq!(VedCoin::allowance(1, 2), 100_000);
+            assert_ok!(VedCoin::transfer_from(RuntimeOrigin::signed(2), 1, 3, 60_000));
+            assert_eq!(VedCoin::allowance(1, 2), 40_000);
+            assert_eq!(Balances::free_balance(3), 1_060_000);
+            assert_noop!(
+                VedCoin::transfer_from(RuntimeOrigin::signed(2), 1, 3, 40_001),
+                Error::<Test>::InsufficientAllowance
+            );
+        });
+    }
+
+    #[test]
+    fn increase_and_decrease_allowance_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::increase_allowance(RuntimeOrigin::signed(1), 2, 500));
+            assert_ok!(VedCoin::increase_allowance(RuntimeOrigin::signed(1), 2, 250));
+            assert_eq!(VedCoin::allowance(1, 2), 750);
+            assert_ok!(VedCoin::decrease_allowance(RuntimeOrigin::signed(1), 2, 700));
+            assert_eq!(VedCoin::allowance(1, 2), 50);
+            assert_noop!(
+                VedCoin::decrease_allowance(RuntimeOrigin::signed(1), 2, 51),
+                Error::<Test>::InsufficientAllowance
+            );
+        });
+    }
+
+    #[test]
+    fn instances_keep_separate_state_and_origins() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedGov::initialize_token(RuntimeOrigin::root(), b"VGV".to_vec(), b"VedGov".to_vec(), 18));
+            assert_eq!(VedCoin::token_info().unwrap().symbol, b"VED".to_vec());
+            assert_eq!(VedGov::token_info().unwrap().symbol, b"VGV".to_vec());
+
+            assert_ok!(VedGov::burn_tokens(RuntimeOrigin::signed(3), 1_000));
+            assert_eq!(VedGov::burned_tokens(), 1_000);
+            assert_eq!(VedCoin::burned_tokens(), 0);
+
+            // Only the registered government account may move VedGov
+            assert_noop!(VedGov::transfer(RuntimeOrigin::signed(1), 2, 100), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedGov::transfer(RuntimeOrigin::signed(3), 2, 100));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100));
+        });
+    }
+
+    #[test]
+    fn instances_lock_and_reserve_their_own_currency() {
+        new_test_ext().execute_with(|| {
+            let schedule = VestingSchedule { locked: 100_000, start: 0, cliff: 0, duration: 10, step: 1 };
+            assert_ok!(VedCoin::vested_transfer(RuntimeOrigin::signed(1), 4, schedule));
+            assert_ok!(VedGov::vested_transfer(RuntimeOrigin::signed(3), 4, schedule));
+
+            // Vesting VedGov removes its own lock, not the one on VedCoin
+            System::set_block_number(10);
+            assert_ok!(VedGov::vest(RuntimeOrigin::signed(4)));
+            assert_ok!(VedGov::transfer(RuntimeOrigin::signed(3), 2, 100));
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(4), 2, 1), sp_runtime::TokenError::Frozen);
+
+            // Staking reserves only the staked instance's currency
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 50_000));
+            assert_eq!(Balances::reserved_balance(2), 50_000);
+            assert_eq!(VedGovBalances::reserved_balance(2), 0);
+            assert_noop!(VedGov::unstake(RuntimeOrigin::signed(2), 1, 50_000), Error::<Test, Instance1>::NotValidator);
+        });
+    }
+
+    #[test]
+    fn frozen_accounts_cannot_send_stake_or_burn() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(5);
+            let reason: BoundedVec<u8, MaxReasonLength> = BoundedVec::try_from(b"court order".to_vec()).unwrap();
+            assert_noop!(VedCoin::freeze_account(RuntimeOrigin::signed(2), 1, reason.clone()), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedCoin::freeze_account(RuntimeOrigin::root(), 1, reason));
+
+            let record = VedCoin::frozen_account(1).unwrap();
+            assert_eq!(record.reason, b"court order".to_vec());
+            assert_eq!(record.since, 5);
+
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(2), 10));
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::AccountFrozen);
+            assert_noop!(VedCoin::stake(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::AccountFrozen);
+            assert_noop!(VedCoin::burn_tokens(RuntimeOrigin::signed(1), 100), Error::<Test>::AccountFrozen);
+            // Frozen accounts can still receive
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(2), 1, 100));
+
+            assert_ok!(VedCoin::unfreeze_account(RuntimeOrigin::root(), 1));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100));
+            assert_noop!(VedCoin::unfreeze_account(RuntimeOrigin::root(), 1), Error::<Test>::NotFrozen);
+        });
+    }
+
+    #[test]
+    fn blocklisted_accounts_cannot_send_or_receive() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::add_to_blocklist(RuntimeOrigin::root(), 3, BoundedVec::try_from(b"sanctions".to_vec()).unwrap()));
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 3, 100), Error::<Test>::AccountBlocklisted);
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(3), 1, 100), Error::<Test>::AccountBlocklisted);
+
+            assert_ok!(VedCoin::remove_from_blocklist(RuntimeOrigin::root(), 3));
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 3, 100));
+
+            // Airdrop claims for a blocklisted account are rejected before they enter the pool
+            let (leaf_4, _) = setup_airdrop();
+            assert_ok!(VedCoin::add_to_blocklist(RuntimeOrigin::root(), 5, BoundedVec::default()));
+            let claim = crate::Call::claim_airdrop {
+                campaign_id: 0,
+                who: 5,
+                amount: 2_000,
+                proof: BoundedVec::try_from(vec![leaf_4]).unwrap(),
+            };
+            assert_eq!(
+                <VedCoin as ValidateUnsigned>::validate_unsigned(TransactionSource::External, &claim),
+                InvalidTransaction::BadSigner.into()
+            );
+        });
+    }
+
+    #[test]
+    fn paused_calls_are_rejected_until_expiry() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_noop!(VedCoin::pause(RuntimeOrigin::signed(1), Some(PausableCall::Transfer)), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedCoin::pause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+            assert_eq!(VedCoin::active_pauses(), vec![(Some(PausableCall::Transfer), 11)]);
+
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::CallPaused);
+            assert_ok!(VedCoin::burn_tokens(RuntimeOrigin::signed(1), 100));
+
+            System::set_block_number(11);
+            assert!(VedCoin::active_pauses().is_empty());
+            assert_ok!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100));
+        });
+    }
+
+    #[test]
+    fn pallet_wide_pause_halts_user_calls() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::pause(RuntimeOrigin::root(), None));
+            assert_noop!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::mint_tokens(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::burn_tokens(RuntimeOrigin::signed(2), 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::transfer(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::register_validator(RuntimeOrigin::signed(2), 10), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::approve(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::increase_allowance(RuntimeOrigin::signed(1), 2, 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::vest(RuntimeOrigin::signed(1)), Error::<Test>::CallPaused);
+            assert_noop!(
+                VedCoin::claim_airdrop(RuntimeOrigin::none(), 0, 1, 100, BoundedVec::default()),
+                Error::<Test>::CallPaused
+            );
+            assert_noop!(VedCoin::sponsor_deposit(RuntimeOrigin::signed(1), 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::sponsor_withdraw(RuntimeOrigin::signed(1), 100), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::release(RuntimeOrigin::signed(1), 0), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::refund(RuntimeOrigin::signed(1), 0), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::withdraw_from_stream(RuntimeOrigin::signed(1), 0), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::cancel_stream(RuntimeOrigin::signed(1), 0), Error::<Test>::CallPaused);
+            assert_noop!(VedCoin::htlc_refund(RuntimeOrigin::signed(1), 1, H256::zero()), Error::<Test>::CallPaused);
+
+            // Governance and compliance stay available
+            assert_ok!(VedCoin::add_minter(RuntimeOrigin::root(), 5, 100));
+            assert_ok!(VedCoin::freeze_account(RuntimeOrigin::root(), 3, BoundedVec::default()));
+            assert_ok!(VedCoin::unfreeze_account(RuntimeOrigin::root(), 3));
+
+            assert_ok!(VedCoin::unpause(RuntimeOrigin::root(), None));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 100));
+            assert_noop!(VedCoin::unpause(RuntimeOrigin::root(), None), Error::<Test>::NotPaused);
+        });
+    }
+
+    #[test]
+    fn mint_tokens_works() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::add_minter(RuntimeOrigin::root(), 5, 500_000));
+            assert_ok!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 500_000));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::TokensMinted { minter: Some(5), to: 1, amount: 500_000 }));
+        });
+    }
+
+    #[test]
+    fn mint_tokens_requires_minter_within_quota() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_noop!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 100), Error::<Test>::NotMinter);
+            assert_noop!(VedCoin::add_minter(RuntimeOrigin::signed(5), 5, 1_000), sp_runtime::DispatchError::BadOrigin);
+            assert_ok!(VedCoin::add_minter(RuntimeOrigin::root(), 5, 1_000));
+            assert_noop!(VedCoin::add_minter(RuntimeOrigin::root(), 5, 1_000), Error::<Test>::AlreadyMinter);
+
+            assert_ok!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 600));
+            assert_noop!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 401), Error::<Test>::MintQuotaExceeded);
+            assert_ok!(VedCoin::set_quota(RuntimeOrigin::root(), 5, 2_000));
+            assert_ok!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 1_400));
+
+            // The quota resets with the next period
+            System::set_block_number(11);
+            assert_ok!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 2_000));
+
+            assert_ok!(VedCoin::remove_minter(RuntimeOrigin::root(), 5));
+            assert_noop!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 1), Error::<Test>::NotMinter);
+        });
+    }
+
+    #[test]
+    fn mint_tokens_respects_max_supply() {
+        new_test_ext().execute_with(|| {
+            leave_headroom(500_000);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::add_minter(RuntimeOrigin::root(), 5, 1_000_000));
+            assert_ok!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 500_000));
+            assert_eq!(VedCoin::token_info().unwrap().total_supply, MaxSupply::get());
+            assert_noop!(VedCoin::mint_tokens(RuntimeOrigin::signed(5), 1, 1), Error::<Test>::MaxSupplyExceeded);
+        });
+    }
+
+    #[test]
+    fn distribute_rewards_respects_max_supply() {
+        new_test_ext().execute_with(|| {
+            leave_headroom(1_000);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 50_000));
+            assert_noop!(VedCoin::distribute_rewards(&1, 1_001), Error::<Test>::MaxSupplyExceeded);
+            assert_ok!(VedCoin::distribute_rewards(&1, 1_000));
+            assert_eq!(VedCoin::token_info().unwrap().total_supply, MaxSupply::get());
+        });
+    }
+
+    #[test]
+    fn fungible_mutate_keeps_supply_in_sync() {
+        use frame_support::traits::tokens::{fungible::{Mutate, Unbalanced, UnbalancedHold}, Fortitude, Precision};
+        new_test_ext().execute_with(|| {
+            leave_headroom(10_000);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(<VedCoin as Mutate<u128>>::mint_into(&4, 10_000));
+            assert_eq!(Balances::free_balance(4), 10_000);
+            assert_eq!(VedCoin::token_info().unwrap().total_supply, MaxSupply::get());
+            assert_noop!(<VedCoin as Mutate<u128>>::mint_into(&4, 1), Error::<Test>::MaxSupplyExceeded);
+
+            assert_ok!(<VedCoin as Mutate<u128>>::burn_from(&4, 4_000, Precision::Exact, Fortitude::Polite));
+            assert_eq!(VedCoin::burned_tokens(), 4_000);
+            assert_eq!(VedCoin::token_info().unwrap().circulating_supply, MaxSupply::get() - 4_000);
+
+            // `set_balance` burns through the accounted path; raw writes are rejected
+            <VedCoin as Mutate<u128>>::set_balance(&4, 5_000);
+            assert_eq!(Balances::free_balance(4), 5_000);
+            assert_eq!(VedCoin::burned_tokens(), 5_000);
+            assert_noop!(<VedCoin as Unbalanced<u128>>::write_balance(&4, 1), sp_runtime::TokenError::Unsupported);
+            assert_noop!(<VedCoin as Mutate<u128>>::shelve(&4, 1), sp_runtime::TokenError::Unsupported);
+            assert_noop!(
+                <VedCoin as UnbalancedHold<u128>>::set_balance_on_hold(&(), &4, 1),
+                sp_runtime::TokenError::Unsupported
+            );
+        });
+    }
+
+    #[test]
+    fn fungible_transfers_apply_transfer_policy() {
+        use frame_support::traits::tokens::{fungible::{Mutate, MutateHold}, Fortitude, Precision, Preservation, Restriction};
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::set_transfer_fee(RuntimeOrigin::root(), 100, Perbill::from_percent(1)));
+            assert_ok!(<VedCoin as Mutate<u128>>::transfer(&1, &2, 10_000, Preservation::Expendable));
+            assert_eq!(Balances::free_balance(1), 1_000_000 - 10_200);
+
+            assert_ok!(VedCoin::add_to_blocklist(RuntimeOrigin::root(), 2, BoundedVec::default()));
+            assert_noop!(
+                <VedCoin as Mutate<u128>>::transfer(&1, &2, 1_000, Preservation::Expendable),
+                Error::<Test>::AccountBlocklisted
+            );
+            assert_noop!(
+                <VedCoin as MutateHold<u128>>::transfer_on_hold(&(), &1, &2, 1_000, Precision::Exact, Restriction::Free, Fortitude::Polite),
+                Error::<Test>::AccountBlocklisted
+            );
+            assert_ok!(VedCoin::remove_from_blocklist(RuntimeOrigin::root(), 2));
+
+            assert_ok!(VedCoin::freeze_account(RuntimeOrigin::root(), 1, BoundedVec::default()));
+            assert_noop!(
+                <VedCoin as MutateHold<u128>>::transfer_and_hold(&(), &1, &2, 1_000, Precision::Exact, Preservation::Expendable, Fortitude::Polite),
+                Error::<Test>::AccountFrozen
+            );
+            assert_ok!(VedCoin::unfreeze_account(RuntimeOrigin::root(), 1));
+
+            assert_ok!(VedCoin::pause(RuntimeOrigin::root(), Some(PausableCall::Transfer)));
+            assert_noop!(
+                <VedCoin as MutateHold<u128>>::transfer_on_hold(&(), &1, &2, 1_000, Precision::Exact, Restriction::Free, Fortitude::Polite),
+                Error::<Test>::CallPaused
+            );
+        });
+    }
+
+    #[test]
+    fn deal_with_fees_burns_share_and_funds_treasury() {
+        use frame_support::traits::{ExistenceRequirement, OnUnbalanced};
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            let fee = Balances::withdraw(&1, 1_000, WithdrawReasons::FEE, ExistenceRequirement::KeepAlive).unwrap();
+            let tip = Balances::withdraw(&1, 500, WithdrawReasons::TIP, ExistenceRequirement::KeepAlive).unwrap();
+            DealWithFees::<Test>::on_unbalanceds(vec![fee, tip].into_iter());
+
+            // 10% of 1_500 is burned, the rest goes to the treasury
+            assert_eq!(Balances::free_balance(VedCoin::treasury_account()), 1_350);
+            assert_eq!(Balances::total_issuance(), 3_000_000 - 150);
+            assert_eq!(VedCoin::burned_tokens(), 150);
+            assert_eq!(VedCoin::token_info().unwrap().circulating_supply, 3_000_000 - 150);
+            assert_eq!(VedCoin::block_fee_burn(), 150);
+
+            VedCoin::on_initialize(2);
+            assert_eq!(VedCoin::block_fee_burn(), 0);
+        });
+    }
+
+    #[test]
+    fn burn_from_spends_allowance_and_records_reason() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::approve(RuntimeOrigin::signed(1), 3, 1_000));
+            assert_ok!(VedCoin::approve(RuntimeOrigin::signed(1), 2, 1_000));
+            assert_noop!(
+                VedCoin::burn_from(RuntimeOrigin::signed(3), 1, 1_001, BurnReason::Bridge),
+                Error::<Test>::InsufficientAllowance
+            );
+
+            // Only the bridge may record bridge burns, and no caller may pick the other reasons
+            assert_noop!(
+                VedCoin::burn_from(RuntimeOrigin::signed(2), 1, 100, BurnReason::Bridge),
+                sp_runtime::DispatchError::BadOrigin
+            );
+            for reason in [BurnReason::Fee, BurnReason::Slash, BurnReason::Expiry] {
+                assert_noop!(VedCoin::burn_from(RuntimeOrigin::signed(3), 1, 100, reason), Error::<Test>::InvalidBurnReason);
+            }
+            assert_ok!(VedCoin::burn_from(RuntimeOrigin::signed(3), 1, 1_000, BurnReason::Bridge));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::TokensBurned { amount: 1_000, reason: BurnReason::Bridge }));
+            assert_eq!(VedCoin::allowance(1, 3), 0);
+            assert_eq!(Balances::free_balance(1), 999_000);
+
+            assert_ok!(VedCoin::burn_from(RuntimeOrigin::signed(2), 1, 200, BurnReason::Voluntary));
+            assert_ok!(VedCoin::burn_tokens(RuntimeOrigin::signed(2), 500));
+            assert_eq!(VedCoin::burned_by_reason(BurnReason::Bridge), 1_000);
+            assert_eq!(VedCoin::burned_by_reason(BurnReason::Voluntary), 700);
+            assert_eq!(VedCoin::burned_tokens(), 1_700);
+            assert_eq!(VedCoin::token_info().unwrap().circulating_supply, 3_000_000 - 1_700);
+        });
+    }
+
+    #[test]
+    fn reconcile_supply_fixes_drift() {
+        new_test_ext().execute_with(|| {
+            System::set_block_number(1);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            // Token info claims a supply that was never minted
+            TokenInfoStorage::<Test>::mutate(|info| {
+                let info = info.as_mut().unwrap();
+                info.total_supply += 2_000_000;
+                info.circulating_supply += 2_000_000;
+            });
+            assert_ok!(VedCoin::burn_tokens(RuntimeOrigin::signed(1), 1_000));
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 500));
+            TotalStaked::<Test>::put(0);
+
+            let report = VedCoin::supply_report();
+            assert!(!report.is_consistent());
+            assert_noop!(VedCoin::reconcile_supply(RuntimeOrigin::signed(1), 1), sp_runtime::DispatchError::BadOrigin);
+            assert_noop!(VedCoin::reconcile_supply(RuntimeOrigin::root(), 0), Error::<Test>::ValidatorWitnessTooLow);
+            assert_ok!(VedCoin::reconcile_supply(RuntimeOrigin::root(), 1));
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::SupplyReconciled {
+                report: SupplyReport {
+                    recorded_circulating: 4_999_000,
+                    actual_circulating: 2_999_000,
+                    recorded_total: 5_000_000,
+                    actual_total: 3_000_000,
+                    recorded_staked: 0,
+                    actual_staked: 500,
+                },
+            }));
+
+            let info = VedCoin::token_info().unwrap();
+            assert_eq!(info.circulating_supply, 2_999_000);
+            assert_eq!(info.total_supply, 3_000_000);
+            assert_eq!(VedCoin::total_staked(), 500);
+            assert!(VedCoin::supply_report().is_consistent());
+        });
+    }
+
+    #[cfg(feature = "try-runtime")]
+    #[test]
+    fn try_state_detects_drift() {
+        use frame_support::traits::Hooks;
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(<VedCoin as Hooks<u64>>::try_state(1));
+            TotalStaked::<Test>::put(1);
+            assert!(<VedCoin as Hooks<u64>>::try_state(1).is_err());
+        });
+    }
+
+    #[test]
+    fn burn_tokens_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::burn_tokens(RuntimeOrigin::signed(1), 100_000));
+        });
+    }
+
+    #[test]
+    fn register_and_stake_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(1), 1, 100_000));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 50_000));
+        });
+    }
+
+    #[test]
+    fn unstake_works() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(1), 1, 100_000));
+            assert_ok!(VedCoin::unstake(RuntimeOrigin::signed(1), 1, 50_000));
+        });
+    }
+
+    #[test]
+    fn staking_uses_named_reserve() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 50_000));
+            assert_ok!(Balances::reserve(&2, 20_000));
+            assert_eq!(VedCoin::staked_balance(&2), 50_000);
+            assert_eq!(Balances::reserved_balance(2), 70_000);
+
+            // Unstaking only touches the staking reserve
+            assert_ok!(VedCoin::unstake(RuntimeOrigin::signed(2), 1, 50_000));
+            assert_eq!(VedCoin::staked_balance(&2), 0);
+            assert_eq!(Balances::reserved_balance(2), 20_000);
+        });
+    }
+
+    #[test]
+    fn migrate_to_v4_moves_stakes_to_named_reserve() {
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+        new_test_ext().execute_with(|| {
+            // Stakes from before the upgrade sit in the anonymous reserve
+            StakingInfoStorage::<Test>::insert(1, StakingInfo {
+                staked: 10_000,
+                delegators: vec![(2, 20_000)],
+                commission: 10,
+                is_validator: true,
+            });
+            TotalStaked::<Test>::put(30_000);
+            assert_ok!(Balances::reserve(&1, 10_000));
+            assert_ok!(Balances::reserve(&2, 25_000));
+            StorageVersion::new(3).put::<VedCoin>();
+
+            crate::migrations::v4::MigrateToV4::<Test>::on_runtime_upgrade();
+            assert_eq!(VedCoin::on_chain_storage_version(), 4);
+            assert_eq!(VedCoin::staked_balance(&1), 10_000);
+            assert_eq!(VedCoin::staked_balance(&2), 20_000);
+            assert_eq!(Balances::reserved_balance(2), 25_000);
+
+            assert_ok!(VedCoin::unstake(RuntimeOrigin::signed(2), 1, 20_000));
+            assert_eq!(Balances::reserved_balance(2), 5_000);
+        });
+    }
+
+    #[test]
+    fn slash_stake_burns_staked_funds() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            assert_ok!(VedCoin::register_validator(RuntimeOrigin::signed(1), 10));
+            assert_ok!(VedCoin::stake(RuntimeOrigin::signed(2), 1, 50_000));
+
+            assert_eq!(VedCoin::slash_stake(&1, &2, 20_000), 20_000);
+            assert_eq!(VedCoin::staked_balance(&2), 30_000);
+            assert_eq!(VedCoin::total_staked(), 30_000);
+            assert_eq!(VedCoin::burned_tokens(), 20_000);
+            assert_eq!(VedCoin::staking_info(1).unwrap().delegators, vec![(2, 30_000)]);
+            assert_noop!(VedCoin::unstake(RuntimeOrigin::signed(2), 1, 30_001), Error::<Test>::InsufficientStake);
+        });
+    }
+
+    #[test]
+    fn emission_follows_halving_schedule() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(VedCoin::annual_emission(0), 1_000_000);
+            assert_eq!(VedCoin::annual_emission(1), 200_000);
+            assert_eq!(VedCoin::annual_emission(4), 200_000);
+            assert_eq!(VedCoin::annual_emission(5), 100_000);
+            assert_eq!(VedCoin::annual_emission(9), 50_000);
+
+            EmissionStart::<Test>::put(1);
+            let remaining = VedCoin::remaining_emission();
+            run_to_block(100);
+            assert_eq!(Balances::free_balance(VedCoin::account_id()), 1_000_000);
+            assert_eq!(VedCoin::total_emitted(), 1_000_000);
+            assert_eq!(VedCoin::remaining_emission(), remaining - 1_000_000);
+
+            run_to_block(101);
+            assert_eq!(Balances::free_balance(VedCoin::account_id()), 1_002_000);
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::EmissionMinted { amount: 2_000, year: 1 }));
+        });
+    }
+
+    #[test]
+    fn emission_years_count_from_emission_start() {
+        use frame_support::traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion};
+        new_test_ext().execute_with(|| {
+            // Nothing is emitted before the upgrade sets the start block
+            run_to_block(250);
+            assert_eq!(VedCoin::total_emitted(), 0);
+
+            StorageVersion::new(2).put::<VedCoin>();
+            crate::migrations::v3::MigrateToV3::<Test>::on_runtime_upgrade();
+            assert_eq!(VedCoin::on_chain_storage_version(), 3);
+            assert_eq!(VedCoin::emission_start(), Some(250));
+
+            // Year 0 runs from the upgrade block, not from genesis
+            assert_eq!(VedCoin::emission_year(349), 0);
+            assert_eq!(VedCoin::emission_year(350), 1);
+            run_to_block(349);
+            assert_eq!(VedCoin::total_emitted(), 1_000_000 - 10_000);
+            run_to_block(350);
+            System::assert_last_event(RuntimeEvent::VedCoin(crate::Event::EmissionMinted { amount: 2_000, year: 1 }));
+        });
+    }
+
+    #[test]
+    fn emission_stops_at_max_supply() {
+        new_test_ext().execute_with(|| {
+            leave_headroom(15_000);
+            assert_ok!(VedCoin::initialize_token(RuntimeOrigin::root(), b"VED".to_vec(), b"VedCoin".to_vec(), 18));
+            EmissionStart::<Test>::put(1);
+            run_to_block(1);
+            assert_eq!(VedCoin::token_info().unwrap().total_supply, MaxSupply::get() - 5_000);
+            run_to_block(5);
+            assert_eq!(VedCoin::token_info().unwrap().total_supply, MaxSupply::get());
+            assert_eq!(VedCoin::total_emitted(), 15_000);
+            assert_eq!(VedCoin::remaining_emission(), 0);
+        });
+    }
+
 }
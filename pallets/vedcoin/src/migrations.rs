@@ -0,0 +1,159 @@
+//! Storage migrations.
+//!
+//! Each migration checks the on-chain storage version, so the runtime can list all of them in
+//! its `Executive` migrations and only the pending ones run.
+
+use super::*;
+use frame_support::{
+    traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+    weights::Weight,
+};
+use sp_std::marker::PhantomData;
+
+/// Adds `max_supply` to the stored `TokenInfo`.
+pub mod v2 {
+    use super::*;
+
+    /// `TokenInfo` as stored before the max-supply cap
+    #[derive(Decode)]
+    struct OldTokenInfo {
+        symbol: Vec<u8>,
+        name: Vec<u8>,
+        decimals: u8,
+        total_supply: u128,
+        circulating_supply: u128,
+    }
+
+    /// Re-encode `TokenInfo` with `max_supply` set to `T::MaxSupply`
+    pub struct MigrateToV2<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV2<T, I> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T, I>::on_chain_storage_version() != 1 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let _ = TokenInfoStorage::<T, I>::translate::<OldTokenInfo, _>(|old| {
+                old.map(|old| TokenInfo {
+                    symbol: old.symbol,
+                    name: old.name,
+                    decimals: old.decimals,
+                    total_supply: old.total_supply,
+                    circulating_supply: old.circulating_supply,
+                    max_supply: T::MaxSupply::get(),
+                })
+            });
+            StorageVersion::new(2).put::<Pallet<T, I>>();
+
+            T::DbWeight::get().reads_writes(2, 2)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            frame_support::ensure!(
+                Pallet::<T, I>::on_chain_storage_version() >= 2,
+                "VedCoin storage version was not bumped to 2"
+            );
+            if TokenInfoStorage::<T, I>::exists() {
+                frame_support::ensure!(
+                    Pallet::<T, I>::token_info().is_some(),
+                    "TokenInfo does not decode"
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Starts the emission schedule at the upgrade block instead of at genesis.
+pub mod v3 {
+    use super::*;
+
+    /// Set `EmissionStart` to the block the upgrade is applied in
+    pub struct MigrateToV3<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV3<T, I> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T, I>::on_chain_storage_version() != 2 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            if !EmissionStart::<T, I>::exists() {
+                EmissionStart::<T, I>::put(frame_system::Pallet::<T>::block_number());
+            }
+            StorageVersion::new(3).put::<Pallet<T, I>>();
+
+            T::DbWeight::get().reads_writes(3, 2)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            frame_support::ensure!(
+                Pallet::<T, I>::on_chain_storage_version() >= 3,
+                "VedCoin storage version was not bumped to 3"
+            );
+            frame_support::ensure!(
+                EmissionStart::<T, I>::exists(),
+                "EmissionStart was not set"
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Moves stakes made before the named staking reserve into it.
+pub mod v4 {
+    use super::*;
+    use sp_std::collections::btree_map::BTreeMap;
+
+    /// Re-reserve every recorded stake under `STAKING_ID`
+    ///
+    /// Stakes used to be held in the anonymous reserve, which `unstake` can no longer release.
+    pub struct MigrateToV4<T, I = ()>(PhantomData<(T, I)>);
+
+    impl<T: Config<I>, I: 'static> OnRuntimeUpgrade for MigrateToV4<T, I> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T, I>::on_chain_storage_version() != 3 {
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut stakes: BTreeMap<T::AccountId, u128> = BTreeMap::new();
+            let mut validators: u64 = 0;
+            for (validator, info) in StakingInfoStorage::<T, I>::iter() {
+                validators = validators.saturating_add(1);
+                let own = stakes.entry(validator).or_default();
+                *own = own.saturating_add(info.staked);
+                for (delegator, amount) in info.delegators {
+                    let delegated = stakes.entry(delegator).or_default();
+                    *delegated = delegated.saturating_add(amount);
+                }
+            }
+
+            let stakers = stakes.len() as u64;
+            for (who, amount) in stakes {
+                if amount.is_zero() {
+                    continue;
+                }
+                let missing = T::Currency::unreserve(&who, amount.saturated_into());
+                let moved = amount.saturating_sub(missing.saturated_into());
+                // Leave the funds free rather than locked if the named reserve cannot be made
+                let _ = T::Currency::reserve_named(&STAKING_ID, &who, moved.saturated_into());
+            }
+            StorageVersion::new(4).put::<Pallet<T, I>>();
+
+            T::DbWeight::get().reads_writes(
+                validators.saturating_add(stakers).saturating_add(1),
+                stakers.saturating_mul(2).saturating_add(1),
+            )
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+            frame_support::ensure!(
+                Pallet::<T, I>::on_chain_storage_version() >= 4,
+                "VedCoin storage version was not bumped to 4"
+            );
+            Ok(())
+        }
+    }
+}
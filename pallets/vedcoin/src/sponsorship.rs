@@ -0,0 +1,210 @@
+//! Fee sponsorship.
+//!
+//! A sponsor reserves VED under `SPONSORSHIP_ID` and grants beneficiaries a fee quota,
+//! optionally restricted to a set of call types. `ChargeSponsored` replaces
+//! `ChargeTransactionPayment` in the runtime's signed extensions: when the signer names a
+//! sponsor, the fee is taken from the sponsor's deposit and routed through `DealWithFees`;
+//! otherwise the wrapped `ChargeTransactionPayment` runs unchanged. Only the fee payment is
+//! replaced, so `CheckNonce` and the other checks must stay in the extension tuple and still run
+//! for sponsored transactions. Tips are never sponsored.
+
+use super::*;
+use frame_support::{
+    dispatch::{DispatchInfo, PostDispatchInfo},
+    traits::OnUnbalanced,
+    CloneNoBound, EqNoBound, PartialEqNoBound,
+};
+use pallet_transaction_payment::{
+    ChargeTransactionPayment, Config as PaymentConfig, OnChargeTransaction,
+};
+use sp_runtime::{
+    traits::{DispatchInfoOf, Dispatchable, PostDispatchInfoOf, SignedExtension},
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+    FixedPointOperand,
+};
+use sp_std::marker::PhantomData;
+
+/// Balance type fees are computed in by `pallet-transaction-payment`
+type FeeBalanceOf<T> =
+    <<T as PaymentConfig>::OnChargeTransaction as OnChargeTransaction<T>>::Balance;
+
+/// Signed extension charging transaction fees to a sponsor instead of the signer
+#[derive(Encode, Decode, CloneNoBound, EqNoBound, PartialEqNoBound, TypeInfo)]
+#[scale_info(skip_type_params(T, I))]
+pub struct ChargeSponsored<T: Config<I> + pallet_transaction_payment::Config, I: 'static> {
+    /// Sponsor paying the fee; `None` falls back to `inner`
+    pub sponsor: Option<T::AccountId>,
+    /// Fee extension charging the signer when the transaction is not sponsored
+    pub inner: ChargeTransactionPayment<T>,
+    _phantom: PhantomData<I>,
+}
+
+impl<T: Config<I> + pallet_transaction_payment::Config, I: 'static> ChargeSponsored<T, I> {
+    /// Wrap `inner`, charging `sponsor` when one is given
+    pub fn new(sponsor: Option<T::AccountId>, inner: ChargeTransactionPayment<T>) -> Self {
+        Self { sponsor, inner, _phantom: PhantomData }
+    }
+}
+
+impl<T: Config<I> + pallet_transaction_payment::Config, I: 'static> sp_std::fmt::Debug
+    for ChargeSponsored<T, I>
+{
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        write!(f, "ChargeSponsored({:?}, {:?})", self.sponsor, self.inner)
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T, I> SignedExtension for ChargeSponsored<T, I>
+where
+    T: Config<I> + pallet_transaction_payment::Config + Send + Sync,
+    I: 'static + Send + Sync,
+    T::RuntimeCall: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+    FeeBalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+{
+    const IDENTIFIER: &'static str = "ChargeSponsored";
+    type AccountId = T::AccountId;
+    type Call = T::RuntimeCall;
+    type AdditionalSigned = ();
+    /// `None` when the fee was paid by a sponsor
+    type Pre = Option<<ChargeTransactionPayment<T> as SignedExtension>::Pre>;
+
+    fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        match &self.sponsor {
+            Some(sponsor) => {
+                Pallet::<T, I>::sponsored_fee(sponsor, who, &call.encode(), info, len)?;
+                Ok(ValidTransaction::default())
+            }
+            None => self.inner.validate(who, call, info, len),
+        }
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        match self.sponsor {
+            Some(sponsor) => {
+                Pallet::<T, I>::charge_sponsor(&sponsor, who, &call.encode(), info, len)?;
+                Ok(None)
+            }
+            None => Ok(Some(self.inner.pre_dispatch(who, call, info, len)?)),
+        }
+    }
+
+    fn post_dispatch(
+        pre: Option<Self::Pre>,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        if let Some(Some(pre)) = pre {
+            ChargeTransactionPayment::<T>::post_dispatch(Some(pre), info, post_info, len, result)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T, I> Pallet<T, I>
+where
+    T: Config<I> + pallet_transaction_payment::Config,
+    I: 'static,
+    T::RuntimeCall: Dispatchable<Info = DispatchInfo>,
+    FeeBalanceOf<T>: FixedPointOperand,
+{
+    /// Fee `sponsor` would pay for `beneficiary` submitting the encoded call
+    ///
+    /// The fee is what `ChargeTransactionPayment` would charge without a tip: base extrinsic
+    /// weight, length and weight fees, scaled by the fee multiplier. The first two bytes of an
+    /// encoded runtime call are its pallet and call index.
+    pub fn sponsored_fee(
+        sponsor: &T::AccountId,
+        beneficiary: &T::AccountId,
+        encoded_call: &[u8],
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<u128, TransactionValidityError> {
+        let quota = Self::sponsorship(sponsor, beneficiary).ok_or(InvalidTransaction::Payment)?;
+
+        let calls = Self::sponsored_calls(sponsor);
+        if !calls.is_empty() {
+            let call_id = match encoded_call {
+                [pallet, call, ..] => (*pallet, *call),
+                _ => return Err(InvalidTransaction::Call.into()),
+            };
+            if !calls.contains(&call_id) {
+                return Err(InvalidTransaction::Call.into());
+            }
+        }
+
+        let fee: u128 =
+            pallet_transaction_payment::Pallet::<T>::compute_fee(len as u32, info, Zero::zero())
+                .saturated_into();
+        let deposit: u128 =
+            T::Currency::reserved_balance_named(&SPONSORSHIP_ID, sponsor).saturated_into();
+        if fee > quota || fee > deposit {
+            return Err(InvalidTransaction::Payment.into());
+        }
+
+        Ok(fee)
+    }
+
+    /// Take the fee from the sponsor's deposit and the beneficiary's quota
+    fn charge_sponsor(
+        sponsor: &T::AccountId,
+        beneficiary: &T::AccountId,
+        encoded_call: &[u8],
+        info: &DispatchInfo,
+        len: usize,
+    ) -> Result<(), TransactionValidityError> {
+        let fee = Self::sponsored_fee(sponsor, beneficiary, encoded_call, info, len)?;
+
+        let (imbalance, _) =
+            T::Currency::slash_reserved_named(&SPONSORSHIP_ID, sponsor, fee.saturated_into());
+        DealWithFees::<T, I>::on_unbalanced(imbalance);
+
+        let remaining = Self::sponsorship(sponsor, beneficiary)
+            .unwrap_or_default()
+            .saturating_sub(fee);
+        Sponsorships::<T, I>::insert(sponsor, beneficiary, remaining);
+
+        Self::deposit_event(Event::FeeSponsored {
+            sponsor: sponsor.clone(),
+            beneficiary: beneficiary.clone(),
+            fee,
+        });
+
+        // Another transaction of the same weight can no longer be covered
+        let deposit: u128 =
+            T::Currency::reserved_balance_named(&SPONSORSHIP_ID, sponsor).saturated_into();
+        if remaining < fee || deposit < fee {
+            Self::deposit_event(Event::SponsorshipExhausted {
+                sponsor: sponsor.clone(),
+                beneficiary: beneficiary.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}